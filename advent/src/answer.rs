@@ -0,0 +1,200 @@
+use crate::{ocr, Grid};
+use serde::{Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// The result of running a part, in a form that can be compared, stored and submitted.
+///
+/// Answers are normalized when they are built, so two answers are equal exactly when they would
+/// be submitted as the same text: integers compare by value regardless of width, text that spells
+/// a plain integer becomes an integer, and grids of block letters become the letters they spell.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer { negative: bool, magnitude: u128 },
+    Text(String),
+    Grid(Grid),
+}
+
+impl Answer {
+    /// Builds an answer from text, trimming trailing whitespace from every line and dropping
+    /// surrounding blank lines.
+    pub fn text(s: &str) -> Self {
+        let lines: Vec<&str> = s.lines().map(str::trim_end).collect();
+        let start = lines
+            .iter()
+            .position(|l| !l.is_empty())
+            .unwrap_or(lines.len());
+        let end = lines
+            .iter()
+            .rposition(|l| !l.is_empty())
+            .map_or(start, |e| e + 1);
+        let lines = &lines[start..end];
+
+        if let [line] = lines {
+            if let Some(answer) = parse_integer(line.trim_start()) {
+                return answer;
+            }
+        }
+
+        let is_picture = lines.len() > 1
+            && lines
+                .iter()
+                .all(|l| l.chars().all(|c| matches!(c, '#' | '.')));
+        if is_picture {
+            let rows = lines
+                .iter()
+                .map(|l| l.chars().map(|c| c == '#').collect())
+                .collect();
+            return Grid::new(rows).into();
+        }
+
+        Self::Text(lines.join("\n"))
+    }
+
+    /// Whether the answer needs more than one line to display.
+    pub fn is_multi_line(&self) -> bool {
+        match self {
+            Self::Integer { .. } => false,
+            Self::Text(s) => s.contains('\n'),
+            Self::Grid(_) => true,
+        }
+    }
+}
+
+fn parse_integer(s: &str) -> Option<Answer> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    let canonical = !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'))
+        && !(negative && digits == "0");
+    if !canonical {
+        return None;
+    }
+    let magnitude = digits.parse().ok()?;
+    Some(Answer::Integer {
+        negative,
+        magnitude,
+    })
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer {
+                negative,
+                magnitude,
+            } => {
+                if *negative {
+                    write!(f, "-")?;
+                }
+                write!(f, "{}", magnitude)
+            }
+            Self::Text(s) => write!(f, "{}", s),
+            Self::Grid(g) => write!(f, "{}", g.picture()),
+        }
+    }
+}
+
+/// In JSON, integers become numbers and everything else a string.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Integer {
+                negative: false,
+                magnitude,
+            } => serializer.serialize_u128(*magnitude),
+            Self::Integer {
+                negative: true,
+                magnitude,
+            } => serializer.serialize_i128((*magnitude as i128).wrapping_neg()),
+            _ => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        Ok(Self::text(s))
+    }
+}
+
+macro_rules! unsigned_answer {
+    ($($id:ty),*) => {
+        $(impl From<$id> for Answer {
+            fn from(n: $id) -> Self {
+                Self::Integer {
+                    negative: false,
+                    magnitude: n as u128,
+                }
+            }
+        })*
+    }
+}
+
+unsigned_answer!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! signed_answer {
+    ($($id:ty),*) => {
+        $(impl From<$id> for Answer {
+            fn from(n: $id) -> Self {
+                Self::Integer {
+                    negative: n < 0,
+                    magnitude: (n as i128).unsigned_abs(),
+                }
+            }
+        })*
+    }
+}
+
+signed_answer!(i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::text(&s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::text(s)
+    }
+}
+
+impl From<Grid> for Answer {
+    fn from(g: Grid) -> Self {
+        match ocr::recognize(&g) {
+            Some(letters) => Self::Text(letters),
+            None => Self::Grid(g.trimmed()),
+        }
+    }
+}
+
+impl From<Vec<Vec<bool>>> for Answer {
+    fn from(rows: Vec<Vec<bool>>) -> Self {
+        Grid::new(rows).into()
+    }
+}
+
+#[test]
+fn normalized_comparison() {
+    assert_eq!(Answer::from(42u8), Answer::from(42i128));
+    assert_eq!(
+        Answer::from(u128::MAX),
+        u128::MAX.to_string().parse().unwrap()
+    );
+    assert_eq!(Answer::from(-7i32), Answer::from(" -7 \n"));
+    assert_ne!(Answer::from("007"), Answer::from(7u32));
+
+    let picture = "\n#..\n.#.\n..#  \n";
+    let grid = Answer::from(picture);
+    assert!(matches!(grid, Answer::Grid(_)));
+    assert_eq!(grid.to_string().parse::<Answer>().unwrap(), grid);
+    assert_eq!(serde_json::to_string(&grid).unwrap(), "\"#..\\n.#.\\n..#\"");
+    assert_eq!(serde_json::to_string(&Answer::from(-7i32)).unwrap(), "-7");
+}
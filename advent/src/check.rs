@@ -0,0 +1,137 @@
+//! Checking parts against the examples' expected answers, for `--check`.
+//!
+//! The expected answers live in `expected.toml` in the day's crate, as written by
+//! `extract_examples.py`, with a table per example file:
+//!
+//! ```toml
+//! [example0]
+//! part1 = "26"
+//! part2 = 26984457539
+//! params = { "part_one.days" = 18 }
+//! ```
+
+use crate::config::value_string;
+use crate::harness::{run_watched, Outcome, Part};
+use crate::params::{clear_param, params, set_param};
+use crate::Answer;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const FILE_NAME: &str = "expected.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expected {
+    part1: Option<toml::Value>,
+    part2: Option<toml::Value>,
+    #[serde(default)]
+    params: BTreeMap<String, toml::Value>,
+}
+
+impl Expected {
+    fn answer(&self, part: usize) -> Option<Answer> {
+        let value = match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        };
+        value.map(|v| Answer::text(&value_string(v)))
+    }
+}
+
+fn check_error(path: &Path, e: impl std::fmt::Display) -> parse::Error {
+    parse::Error::ParseError(format!("{}: {}", path.display(), e.to_string().trim_end()))
+}
+
+/// Sets the example's parameters for as long as it is alive, then puts back what was there.
+struct ParamsGuard(Vec<(String, Option<String>)>);
+
+impl ParamsGuard {
+    fn set(overrides: &BTreeMap<String, toml::Value>) -> Self {
+        let current = params();
+        let previous = overrides
+            .iter()
+            .map(|(name, value)| {
+                let previous = current
+                    .iter()
+                    .find(|(n, ..)| n == name)
+                    .and_then(|(_, _, v)| v.clone());
+                set_param(name, &value_string(value));
+                (name.clone(), previous)
+            })
+            .collect();
+        Self(previous)
+    }
+}
+
+impl Drop for ParamsGuard {
+    fn drop(&mut self) {
+        for (name, previous) in &self.0 {
+            match previous {
+                Some(value) => set_param(name, value),
+                None => clear_param(name),
+            }
+        }
+    }
+}
+
+/// Runs the selected parts on every example in `expected.toml` that has an expected answer for
+/// them, printing a line per check. Returns how many checks were run and how many failed.
+pub fn check(
+    dir: &Path,
+    parts: &[(usize, Part)],
+    timeout: Option<Duration>,
+) -> parse::Result<(usize, usize)> {
+    let path = dir.join(FILE_NAME);
+    let contents = fs::read_to_string(&path).map_err(|e| check_error(&path, e))?;
+    let examples: BTreeMap<String, Expected> =
+        toml::from_str(&contents).map_err(|e| check_error(&path, e))?;
+
+    let (mut checked, mut failed) = (0, 0);
+    for (name, expected) in &examples {
+        let example = dir.join(format!("{}.txt", name));
+        let input: Arc<str> = fs::read_to_string(&example)
+            .map_err(|e| check_error(&example, e))?
+            .into();
+        let _params = ParamsGuard::set(&expected.params);
+
+        for &(number, part) in parts {
+            let want = match expected.answer(number) {
+                Some(want) => want,
+                None => continue,
+            };
+            checked += 1;
+            let (outcome, _) = run_watched(number, part, input.clone(), timeout)?;
+            let problem = match outcome {
+                Outcome::Answer(got) if got == want => None,
+                Outcome::Answer(got) => Some(format!("expected {}, got {}", want, got)),
+                Outcome::Panicked(p) => Some(p.to_string()),
+                Outcome::TimedOut(t) => Some(format!("timed out after {:?}", t)),
+            };
+            match problem {
+                None => println!("{} part {}: ok", name, number),
+                Some(problem) => {
+                    failed += 1;
+                    println!("{} part {}: {}", name, number, problem);
+                }
+            }
+        }
+    }
+    Ok((checked, failed))
+}
+
+#[test]
+fn expected_answers() {
+    let examples: BTreeMap<String, Expected> =
+        toml::from_str("[example0]\npart1 = \"26\"\npart2 = 26984457539\nparams = { days = 18 }\n")
+            .unwrap();
+    let example = &examples["example0"];
+    assert_eq!(example.answer(1), Some(Answer::from(26u8)));
+    assert_eq!(example.answer(2), Some(Answer::from(26984457539u64)));
+    assert_eq!(example.answer(3), None);
+    assert_eq!(value_string(&example.params["days"]), "18");
+}
@@ -0,0 +1,167 @@
+//! Harness defaults from `advent.toml` at the workspace root.
+//!
+//! Top-level keys apply to every day and a table named after a day's crate overrides them for that
+//! day. Command line options override both.
+//!
+//! ```toml
+//! timeout = 60
+//! normalize_input = true
+//!
+//! [six]
+//! input = "example.txt"
+//! params = { "part_one.days" = 18 }
+//! ```
+
+use crate::harness::OutputFormat;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = "advent.toml";
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    /// Input file, relative to the day's crate, or "-" for stdin.
+    pub input: Option<PathBuf>,
+    pub format: Option<OutputFormat>,
+    /// Seconds a part may run before it is abandoned.
+    pub timeout: Option<f64>,
+    /// How many times `--profile` runs each part, rather than as many as fit in a few seconds.
+    pub profile_iterations: Option<u32>,
+    /// Whether to strip carriage returns and trailing whitespace from the input.
+    pub normalize_input: Option<bool>,
+    #[serde(default)]
+    pub params: BTreeMap<String, toml::Value>,
+}
+
+impl Settings {
+    /// Overrides these settings with whatever `other` sets.
+    fn merge(mut self, other: Settings) -> Self {
+        self.input = other.input.or(self.input);
+        self.format = other.format.or(self.format);
+        self.timeout = other.timeout.or(self.timeout);
+        self.profile_iterations = other.profile_iterations.or(self.profile_iterations);
+        self.normalize_input = other.normalize_input.or(self.normalize_input);
+        self.params.extend(other.params);
+        self
+    }
+
+    /// The parameter overrides, with values as they would be given to `--param`.
+    pub fn param_values(&self) -> impl Iterator<Item = (&str, String)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value_string(value)))
+    }
+}
+
+/// A value as it would be written on the command line: strings without their quotes.
+pub(crate) fn value_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+fn config_error(path: &Path, e: impl std::fmt::Display) -> parse::Error {
+    parse::Error::ParseError(format!("{}: {}", path.display(), e.to_string().trim_end()))
+}
+
+/// Parses a config file's contents and returns the settings for `day`.
+fn settings_for(contents: &str, day: &str) -> Result<Settings, String> {
+    let table: toml::Table = contents
+        .parse()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    let mut defaults = toml::Table::new();
+    let mut day_section = None;
+    for (key, value) in table {
+        match value {
+            toml::Value::Table(section) if key != "params" => {
+                if key == day {
+                    day_section = Some(section);
+                }
+            }
+            value => {
+                defaults.insert(key, value);
+            }
+        }
+    }
+
+    let parse = |t: toml::Table| {
+        toml::Value::Table(t)
+            .try_into::<Settings>()
+            .map_err(|e| e.to_string())
+    };
+    let mut settings = parse(defaults)?;
+    if let Some(section) = day_section {
+        settings = settings.merge(parse(section).map_err(|e| format!("[{}]: {}", day, e))?);
+    }
+    if settings.timeout.is_some_and(|t| t.is_nan() || t <= 0.0) {
+        return Err("timeout must be a positive number of seconds".into());
+    }
+    Ok(settings)
+}
+
+/// Finds `advent.toml` in the day's crate directory or any directory above it, and returns the
+/// settings for that day. Relative input paths are resolved against the crate directory.
+pub fn load(manifest_dir: &str) -> parse::Result<Settings> {
+    let manifest_dir = Path::new(manifest_dir);
+    let path = match manifest_dir
+        .ancestors()
+        .map(|d| d.join(FILE_NAME))
+        .find(|p| p.is_file())
+    {
+        Some(path) => path,
+        None => return Ok(Settings::default()),
+    };
+
+    let contents = fs::read_to_string(&path).map_err(|e| config_error(&path, e))?;
+    let day = manifest_dir
+        .file_name()
+        .map_or(String::new(), |d| d.to_string_lossy().into_owned());
+    let mut settings = settings_for(&contents, &day).map_err(|e| config_error(&path, e))?;
+    if let Some(input) = &settings.input {
+        if input != Path::new("-") {
+            settings.input = Some(manifest_dir.join(input));
+        }
+    }
+    Ok(settings)
+}
+
+#[test]
+fn day_overrides() {
+    let contents = r#"
+        timeout = 60
+        normalize_input = true
+        params = { steps = 10 }
+
+        [six]
+        input = "example.txt"
+        timeout = 5
+        params = { days = 18 }
+
+        [seven]
+        format = "json"
+    "#;
+
+    let six = settings_for(contents, "six").unwrap();
+    assert_eq!(six.input, Some("example.txt".into()));
+    assert_eq!(six.timeout, Some(5.0));
+    assert_eq!(six.normalize_input, Some(true));
+    assert_eq!(six.format, None);
+    let params: Vec<_> = six.param_values().collect();
+    assert_eq!(
+        params,
+        [("days", "18".to_string()), ("steps", "10".to_string())]
+    );
+
+    let two = settings_for(contents, "two").unwrap();
+    assert_eq!(two.timeout, Some(60.0));
+    assert_eq!(two.input, None);
+
+    assert!(settings_for("timeout = -1\n", "six").is_err());
+    assert!(settings_for("[six]\ntimout = 5\n", "six")
+        .unwrap_err()
+        .contains("[six]"));
+}
@@ -0,0 +1,92 @@
+use crate::ocr;
+use std::fmt;
+
+/// A two-dimensional picture of lit (`#`) and unlit (`.`) cells.
+///
+/// When returned from a part, the grid is run through [`ocr::recognize`] so that pictures of
+/// block letters are reported as the letters themselves.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Grid(Vec<Vec<bool>>);
+
+impl Grid {
+    pub fn new(rows: Vec<Vec<bool>>) -> Self {
+        Self(rows)
+    }
+
+    pub fn height(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.0.iter().map(|r| r.len()).max().unwrap_or(0)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.0
+            .get(y)
+            .and_then(|r| r.get(x))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    pub fn rows(&self) -> &[Vec<bool>] {
+        &self.0
+    }
+
+    /// Returns the smallest rectangular grid containing every lit cell.
+    pub fn trimmed(&self) -> Self {
+        let lit_rows: Vec<usize> = (0..self.height())
+            .filter(|&y| self.0[y].iter().any(|&c| c))
+            .collect();
+        let lit_columns: Vec<usize> = (0..self.width())
+            .filter(|&x| (0..self.height()).any(|y| self.get(x, y)))
+            .collect();
+        let (rows, columns) = match (lit_rows.first(), lit_columns.first()) {
+            (Some(&top), Some(&left)) => (
+                top..=*lit_rows.last().unwrap(),
+                left..=*lit_columns.last().unwrap(),
+            ),
+            _ => return Self::default(),
+        };
+        Self(
+            rows.map(|y| columns.clone().map(|x| self.get(x, y)).collect())
+                .collect(),
+        )
+    }
+
+    /// Displays the grid as a `#`/`.` picture, skipping letter recognition.
+    pub fn picture(&self) -> Picture<'_> {
+        Picture(self)
+    }
+}
+
+impl From<Vec<Vec<bool>>> for Grid {
+    fn from(rows: Vec<Vec<bool>>) -> Self {
+        Self(rows)
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match ocr::recognize(self) {
+            Some(text) => write!(f, "{}", text),
+            None => write!(f, "{}", self.picture()),
+        }
+    }
+}
+
+pub struct Picture<'a>(&'a Grid);
+
+impl fmt::Display for Picture<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.0.rows().iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for &cell in row {
+                write!(f, "{}", if cell { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,494 @@
+use crate::answer::Answer;
+use crate::check;
+use crate::config::{self, Settings};
+use crate::minimize;
+use crate::params::set_param;
+use crate::progress::{self, Snapshot};
+use crate::repl::{self, Parsed};
+use parse::{List, NewLine};
+use std::io::Read as _;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{env, fmt, fs, process};
+
+/// The functions generated by `#[part_one]` and `#[part_two]` for one part of the puzzle.
+#[derive(Clone, Copy)]
+pub struct Part {
+    /// Parses the input and runs the part.
+    pub run: fn(&str) -> parse::Result<Answer>,
+    /// Parses the input and keeps it for running the part from the REPL.
+    pub repl: fn(&str) -> parse::Result<Parsed>,
+}
+
+/// Picks how the generated code parses a part's whole input, by method resolution: lists of
+/// lines are parsed line by line so that every bad line is reported, anything else at once.
+pub struct WholeInput<T>(pub PhantomData<T>);
+
+pub trait ViaLines<T> {
+    fn parse(&self, input: &str) -> parse::Result<T>;
+}
+
+impl<T: parse::HasParser> ViaLines<List<T, NewLine>> for WholeInput<List<T, NewLine>> {
+    fn parse(&self, input: &str) -> parse::Result<List<T, NewLine>> {
+        parse::parse_lines(input)
+    }
+}
+
+pub trait ViaParser<T> {
+    fn parse(&self, input: &str) -> parse::Result<T>;
+}
+
+impl<T: parse::HasParser> ViaParser<T> for &WholeInput<T> {
+    fn parse(&self, input: &str) -> parse::Result<T> {
+        parse::parse_str(input)
+    }
+}
+
+/// Where and why a part panicked.
+#[derive(Clone, Debug)]
+pub struct PanicReport {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl fmt::Display for PanicReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "panicked at '{}'", self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, ", {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Outcome {
+    Answer(Answer),
+    Panicked(PanicReport),
+    TimedOut(Duration),
+}
+
+#[cfg(panic = "unwind")]
+mod isolation {
+    use super::PanicReport;
+    use std::cell::{Cell, RefCell};
+    use std::panic;
+    use std::sync::Once;
+
+    thread_local! {
+        static LAST_PANIC: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+        /// How many `catch` calls are running on this thread.
+        static CATCHING: Cell<usize> = const { Cell::new(0) };
+    }
+
+    static INSTALL_HOOK: Once = Once::new();
+
+    /// Installs, once per process, a panic hook that records panics on threads inside `catch`
+    /// and leaves every other panic to the hook that was there before.
+    fn install_hook() {
+        INSTALL_HOOK.call_once(|| {
+            let previous_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if CATCHING.with(Cell::get) == 0 {
+                    return previous_hook(info);
+                }
+                let report = PanicReport {
+                    message: payload_message(info.payload()),
+                    location: info.location().map(|l| l.to_string()),
+                };
+                LAST_PANIC.with(|p| *p.borrow_mut() = Some(report));
+            }));
+        });
+    }
+
+    fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+        if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Box<dyn Any>".into()
+        }
+    }
+
+    /// Runs `f`, catching any panic and reporting its message and location instead of printing
+    /// it.
+    pub fn catch<R>(f: impl FnOnce() -> R) -> Result<R, PanicReport> {
+        install_hook();
+        CATCHING.with(|c| c.set(c.get() + 1));
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+        CATCHING.with(|c| c.set(c.get() - 1));
+
+        result.map_err(|payload| {
+            LAST_PANIC
+                .with(|p| p.borrow_mut().take())
+                .unwrap_or_else(|| PanicReport {
+                    message: payload_message(&*payload),
+                    location: None,
+                })
+        })
+    }
+}
+
+/// Runs a part, isolating any panic from the rest of the harness when the profile unwinds, as
+/// both the dev and release profiles do. Under `panic = 'abort'` a panic still takes down the
+/// process.
+pub fn run_isolated(f: impl FnOnce() -> parse::Result<Answer>) -> parse::Result<Outcome> {
+    #[cfg(panic = "unwind")]
+    let result = isolation::catch(f);
+    #[cfg(not(panic = "unwind"))]
+    let result: Result<_, PanicReport> = Ok(f());
+
+    match result {
+        Ok(answer) => Ok(Outcome::Answer(answer?)),
+        Err(report) => Ok(Outcome::Panicked(report)),
+    }
+}
+
+pub fn run_part(part: Part, input: &str) -> parse::Result<Outcome> {
+    run_isolated(|| (part.run)(input))
+}
+
+/// Runs a part on its own thread, showing its progress and giving up on it after `timeout`.
+pub(crate) fn run_watched(
+    number: usize,
+    part: Part,
+    input: Arc<str>,
+    timeout: Option<Duration>,
+) -> parse::Result<(Outcome, Option<Snapshot>)> {
+    let name = format!("Part {}", number);
+    match progress::watch(&name, timeout, move || run_part(part, &input)) {
+        (Some(outcome), snapshot) => Ok((outcome?, snapshot)),
+        (None, snapshot) => Ok((Outcome::TimedOut(timeout.unwrap()), snapshot)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// A line of `--json` output, which `minimize` reads back.
+#[derive(serde::Serialize)]
+struct JsonReport<'a> {
+    part: usize,
+    #[serde(flatten)]
+    outcome: JsonOutcome<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    progress: Option<&'a Snapshot>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum JsonOutcome<'a> {
+    Answer {
+        answer: &'a Answer,
+    },
+    Panicked {
+        panic: &'a str,
+        location: Option<&'a str>,
+    },
+    TimedOut {
+        timeout: f64,
+    },
+}
+
+impl OutputFormat {
+    pub(crate) fn report(self, part: usize, outcome: &Outcome, progress: Option<&Snapshot>) {
+        if self == Self::Json {
+            let outcome = match outcome {
+                Outcome::Answer(answer) => JsonOutcome::Answer { answer },
+                Outcome::Panicked(p) => JsonOutcome::Panicked {
+                    panic: &p.message,
+                    location: p.location.as_deref(),
+                },
+                Outcome::TimedOut(t) => JsonOutcome::TimedOut {
+                    timeout: t.as_secs_f64(),
+                },
+            };
+            let report = JsonReport {
+                part,
+                outcome,
+                progress,
+            };
+            println!("{}", serde_json::to_string(&report).unwrap());
+            return;
+        }
+        match outcome {
+            Outcome::Answer(a) if a.is_multi_line() => println!("Part {}:\n{}", part, a),
+            Outcome::Answer(a) => println!("Part {}: {}", part, a),
+            Outcome::Panicked(p) => eprintln!("Part {} {}", part, p),
+            Outcome::TimedOut(t) => match progress {
+                Some(p) => eprintln!("Part {} timed out after {:?} at {}", part, t, p),
+                None => eprintln!("Part {} timed out after {:?}", part, t),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Options {
+    format: OutputFormat,
+    part: Option<usize>,
+    profile: bool,
+    repl: bool,
+    minimize: bool,
+    check: bool,
+    /// The `--param` arguments, passed on to the runs made by `--minimize`.
+    params: Vec<String>,
+    /// The `--variant` arguments, which `--minimize` compares the part's answers against.
+    variant: Vec<String>,
+    input: Option<PathBuf>,
+    timeout: Option<Duration>,
+    profile_iterations: Option<u32>,
+    normalize_input: bool,
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: {} [--json] [--part N] [--param NAME=VALUE]... [--timeout SECS] \
+         [--profile | --repl | --minimize [--variant NAME=VALUE]... | --check] \
+         [--input PATH | < input]",
+        env::args().next().unwrap()
+    );
+    process::exit(2)
+}
+
+impl Options {
+    /// Starts from the settings in `advent.toml` and applies the command line on top.
+    fn from_args(settings: &Settings) -> Self {
+        for (name, value) in settings.param_values() {
+            set_param(name, &value);
+        }
+        let mut options = Self {
+            format: settings.format.unwrap_or(OutputFormat::Text),
+            part: None,
+            profile: false,
+            repl: false,
+            minimize: false,
+            check: false,
+            params: vec![],
+            variant: vec![],
+            input: settings.input.clone(),
+            timeout: settings.timeout.map(Duration::from_secs_f64),
+            profile_iterations: settings.profile_iterations,
+            normalize_input: settings.normalize_input.unwrap_or(false),
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => options.format = OutputFormat::Json,
+                "--part" => {
+                    let part = args.next().and_then(|n| n.parse().ok());
+                    options.part = Some(part.unwrap_or_else(|| usage()));
+                }
+                "--param" => {
+                    let param = args.next().unwrap_or_else(|| usage());
+                    let (name, value) = param.split_once('=').unwrap_or_else(|| usage());
+                    set_param(name, value);
+                    options.params.push(param.clone());
+                }
+                "--variant" => {
+                    let param = args.next().filter(|p| p.contains('='));
+                    options.variant.push(param.unwrap_or_else(|| usage()));
+                }
+                "--timeout" => {
+                    let secs = args.next().and_then(|s| s.parse().ok());
+                    let secs: f64 = secs.filter(|&s: &f64| s > 0.0).unwrap_or_else(|| usage());
+                    options.timeout = Some(Duration::from_secs_f64(secs));
+                }
+                "--profile" => options.profile = true,
+                "--repl" => options.repl = true,
+                "--minimize" => options.minimize = true,
+                "--check" => options.check = true,
+                "--input" => options.input = Some(args.next().unwrap_or_else(|| usage()).into()),
+                _ => usage(),
+            }
+        }
+        if [
+            options.profile,
+            options.repl,
+            options.minimize,
+            options.check,
+        ]
+        .iter()
+        .filter(|&&m| m)
+        .count()
+            > 1
+            || (!options.variant.is_empty() && !options.minimize)
+        {
+            usage();
+        }
+        options
+    }
+
+    /// Reads the puzzle input from the `--input` file, or stdin if there is none or it is "-".
+    /// The REPL needs stdin for its commands, so it reads the crate's `input.txt` by default
+    /// instead.
+    fn read_input(&self, manifest_dir: &str) -> parse::Result<String> {
+        let default = self.repl.then(|| Path::new(manifest_dir).join("input.txt"));
+        let input = match self.input.clone().or(default) {
+            Some(path) if path != Path::new("-") => fs::read_to_string(path)?,
+            _ => {
+                let mut input = String::new();
+                std::io::stdin().lock().read_to_string(&mut input)?;
+                input
+            }
+        };
+        if self.normalize_input {
+            Ok(normalize_input(&input))
+        } else {
+            Ok(input)
+        }
+    }
+}
+
+/// Drops carriage returns, trailing whitespace on each line and trailing blank lines, and ends the
+/// input with exactly one newline.
+fn normalize_input(input: &str) -> String {
+    let mut lines: Vec<&str> = input.lines().map(str::trim_end).collect();
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    let mut normalized = lines.join("\n");
+    normalized.push('\n');
+    normalized
+}
+
+#[cfg(unix)]
+fn profile(
+    number: usize,
+    part: Part,
+    input: &str,
+    manifest_dir: &str,
+    iterations: Option<u32>,
+) -> parse::Result<Outcome> {
+    let (outcome, files) =
+        crate::profile::profile_part(number, part, input, manifest_dir.as_ref(), iterations)?;
+    for f in files {
+        eprintln!("wrote {}", f.display());
+    }
+    Ok(outcome)
+}
+
+#[cfg(not(unix))]
+fn profile(_: usize, _: Part, _: &str, _: &str, _: Option<u32>) -> parse::Result<Outcome> {
+    eprintln!("--profile is only supported on unix");
+    process::exit(2)
+}
+
+/// The body of the `main` function generated by `harness!()`. `manifest_dir` is the directory of
+/// the puzzle's crate, where any output files are written and where `advent.toml` is looked for.
+pub fn main(manifest_dir: &str, parts: &[Part]) {
+    if let Err(e) = try_main(manifest_dir, parts) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn try_main(manifest_dir: &str, parts: &[Part]) -> parse::Result<()> {
+    let options = Options::from_args(&config::load(manifest_dir)?);
+    if options.part.is_some_and(|n| n == 0 || n > parts.len()) {
+        usage();
+    }
+
+    let selected: Vec<(usize, Part)> = (1..)
+        .zip(parts.iter().copied())
+        .filter(|&(n, _)| options.part.is_none_or(|p| p == n))
+        .collect();
+
+    if options.check {
+        let (checked, failed) = check::check(Path::new(manifest_dir), &selected, options.timeout)?;
+        if failed > 0 {
+            eprintln!("{} of {} checks failed", failed, checked);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let input = options.read_input(manifest_dir)?;
+    if options.repl {
+        return repl::run(parts, &input);
+    }
+
+    if options.minimize {
+        let numbers: Vec<usize> = selected.iter().map(|&(n, _)| n).collect();
+        let dir = Path::new(manifest_dir);
+        match minimize::minimize(
+            &numbers,
+            &input,
+            options.timeout,
+            &options.params,
+            &options.variant,
+            dir,
+        )? {
+            Some(path) => eprintln!("wrote {}", path.display()),
+            None => eprintln!("no part fails on this input"),
+        }
+        return Ok(());
+    }
+
+    let input: Arc<str> = input.into();
+    let mut failed = 0;
+    for &(number, part) in &selected {
+        let (outcome, progress) = if options.profile {
+            (
+                profile(
+                    number,
+                    part,
+                    &input,
+                    manifest_dir,
+                    options.profile_iterations,
+                )?,
+                None,
+            )
+        } else {
+            run_watched(number, part, input.clone(), options.timeout)?
+        };
+        if !matches!(outcome, Outcome::Answer(_)) {
+            failed += 1;
+        }
+        options.format.report(number, &outcome, progress.as_ref());
+    }
+
+    if failed > 0 {
+        eprintln!("{} of {} parts failed", failed, selected.len());
+        process::exit(1);
+    }
+    Ok(())
+}
+
+#[test]
+fn panic_is_isolated() {
+    fn panics(_: &str) -> parse::Result<Answer> {
+        panic!("No winner")
+    }
+
+    match run_isolated(|| panics("")).unwrap() {
+        Outcome::Panicked(report) => {
+            assert_eq!(report.message, "No winner");
+            assert!(report.location.unwrap().contains("harness.rs"));
+        }
+        other => panic!("unexpected outcome {:?}", other),
+    }
+
+    // Parts running at the same time each get their own report.
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let outcome = run_isolated(|| panic!("part {}", i)).unwrap();
+                match outcome {
+                    Outcome::Panicked(report) => assert_eq!(report.message, format!("part {}", i)),
+                    other => panic!("unexpected outcome {:?}", other),
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+//! Support for the `hot` development host, which loads a day built as a `cdylib` and keeps its
+//! parsed input alive across reloads of the library.
+//!
+//! A rebuilt library can only be trusted to lay a kept value out the same way as the library that
+//! parsed it when the value's type and parser come from code that wasn't rebuilt: the crates the
+//! day depends on, or the day's own `src/lib.rs` if it has one. The host checks that, and parses
+//! the input again for types defined next to the parts in `src/main.rs`. Old libraries are never
+//! unloaded, so the code to drop a kept value stays around as long as the value does.
+
+use crate::harness::Outcome;
+use std::mem;
+
+/// The symbol under which `harness!()` exports a day's parts.
+pub const PARTS_SYMBOL: &[u8] = b"_advent_hot_parts";
+
+/// The type of the exported symbol.
+pub type PartsFn = fn() -> &'static [HotPart];
+
+/// Identifies a parsed type well enough to tell when a rebuilt library has changed it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    pub type_name: String,
+    pub size: usize,
+    pub align: usize,
+}
+
+impl Layout {
+    pub fn of<T>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>().into(),
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+        }
+    }
+}
+
+/// A parsed value kept by the host, whose type only the libraries know.
+pub struct Kept {
+    ptr: *mut (),
+    drop: unsafe fn(*mut ()),
+    layout: Layout,
+}
+
+unsafe fn drop_boxed<T>(ptr: *mut ()) {
+    drop(Box::from_raw(ptr as *mut T))
+}
+
+impl Kept {
+    pub fn new<T: 'static>(value: T) -> Self {
+        Self {
+            ptr: Box::into_raw(Box::new(value)) as *mut (),
+            drop: drop_boxed::<T>,
+            layout: Layout::of::<T>(),
+        }
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// # Safety
+    ///
+    /// `T` must be the type the value was created with, laid out the same way.
+    pub unsafe fn get<T>(&self) -> &T {
+        debug_assert_eq!(self.layout, Layout::of::<T>());
+        &*(self.ptr as *const T)
+    }
+}
+
+impl Drop for Kept {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(self.ptr) }
+    }
+}
+
+/// How the host parses a part's input into a value it keeps.
+#[derive(Clone, Copy)]
+pub struct Keep {
+    /// The layout of the part's parsed type in this library.
+    pub layout: fn() -> Layout,
+    pub parse: fn(&str) -> parse::Result<Kept>,
+}
+
+/// A part as the host runs it.
+#[derive(Clone, Copy)]
+pub struct HotPart {
+    /// Parts whose parsed value borrows from the input have nothing to keep, and parse it again
+    /// on every run.
+    pub keep: Option<Keep>,
+    /// Runs the part on a clone of the kept value, or on the input parsed again if there is no
+    /// value or it can't be cloned, isolating any panic.
+    ///
+    /// # Safety
+    ///
+    /// A kept value must have been parsed by a library that lays out its type the same way.
+    pub run: unsafe fn(Option<&Kept>, &str) -> parse::Result<Outcome>,
+}
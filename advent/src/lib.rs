@@ -1,4 +1,25 @@
+mod answer;
+mod check;
+mod config;
+mod grid;
+pub mod harness;
+pub mod hot;
+mod minimize;
+pub mod ocr;
+mod params;
+#[cfg(unix)]
+mod profile;
+mod progress;
+pub mod repl;
+pub mod snapshot;
+
+pub use answer::Answer;
+pub use grid::{Grid, Picture};
+pub use params::{clear_param, param, params, set_param};
+pub use progress::{progress, Progress, Snapshot};
+
 pub mod prelude {
+    pub use super::{param, progress, Grid, Progress};
     pub use advent_macro::*;
     pub use parse::prelude::*;
 }
@@ -0,0 +1,289 @@
+//! Delta debugging of failing inputs, for `--minimize`.
+//!
+//! Every candidate input is run in a child process of the same binary, so that aborting panics
+//! and parts that never finish can be survived. A candidate counts as failing only if it fails
+//! the same way the original input did: a panic at the same location, a timeout, a crash, or
+//! with `--variant`, a different answer when the variant's parameters are set too. Candidates
+//! that no longer parse don't fail.
+
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Failure {
+    Panic {
+        location: Option<String>,
+    },
+    Timeout,
+    Crash,
+    /// The part answered differently with the variant's parameters.
+    Disagreement,
+}
+
+/// The JSON the child prints for the part, as written by `OutputFormat::report`.
+#[derive(Debug, Deserialize)]
+struct Report {
+    answer: Option<serde_json::Value>,
+    panic: Option<String>,
+    location: Option<String>,
+    timeout: Option<f64>,
+}
+
+impl Report {
+    /// The part's answer, or how it failed to give one.
+    fn into_result(self) -> Result<serde_json::Value, Failure> {
+        match self {
+            Self {
+                panic: Some(_),
+                location,
+                ..
+            } => Err(Failure::Panic { location }),
+            Self {
+                timeout: Some(_), ..
+            } => Err(Failure::Timeout),
+            Self {
+                answer: Some(answer),
+                ..
+            } => Ok(answer),
+            Self { .. } => Err(Failure::Crash),
+        }
+    }
+}
+
+fn report_from_json(line: &str) -> Option<Report> {
+    serde_json::from_str(line).ok()
+}
+
+struct Minimizer {
+    exe: PathBuf,
+    part: usize,
+    timeout: Option<Duration>,
+    params: Vec<String>,
+    /// The `--variant` parameters, set on top of `params` for a second run to compare against.
+    variant: Vec<String>,
+    scratch: PathBuf,
+    runs: usize,
+}
+
+impl Minimizer {
+    /// Runs the part on `input` with `extra` parameters on top of the `--param` ones. Returns its
+    /// answer, or `None` if it didn't report one because the input doesn't parse.
+    fn run_with(
+        &mut self,
+        input: &str,
+        extra: &[String],
+    ) -> io::Result<Option<Result<serde_json::Value, Failure>>> {
+        self.runs += 1;
+        fs::write(&self.scratch, input)?;
+        let mut command = Command::new(&self.exe);
+        command
+            .args(["--json", "--part", &self.part.to_string(), "--input"])
+            .arg(&self.scratch)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(timeout) = self.timeout {
+            command.args(["--timeout", &timeout.as_secs_f64().to_string()]);
+        }
+        for param in self.params.iter().chain(extra) {
+            command.args(["--param", param]);
+        }
+        let output = command.output()?;
+        if output.status.code().is_none() {
+            return Ok(Some(Err(Failure::Crash)));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(report_from_json)
+            .map(Report::into_result))
+    }
+
+    fn run(&mut self, input: &str) -> io::Result<Option<Failure>> {
+        let answer = match self.run_with(input, &[])? {
+            Some(Ok(answer)) => answer,
+            Some(Err(failure)) => return Ok(Some(failure)),
+            None => return Ok(None),
+        };
+        if self.variant.is_empty() {
+            return Ok(None);
+        }
+        let variant = self.variant.clone();
+        Ok(match self.run_with(input, &variant)? {
+            Some(Ok(other)) if other != answer => Some(Failure::Disagreement),
+            Some(Ok(_)) | None => None,
+            Some(Err(failure)) => Some(failure),
+        })
+    }
+}
+
+/// Finds a small subsequence of `units` for which `fails` still holds, by removing ever smaller
+/// chunks of it.
+fn ddmin<T: Clone>(
+    mut units: Vec<T>,
+    mut fails: impl FnMut(&[T]) -> io::Result<bool>,
+) -> io::Result<Vec<T>> {
+    let mut chunks = 2;
+    while units.len() >= 2 {
+        let size = units.len().div_ceil(chunks);
+        let mut reduced = false;
+        for start in (0..units.len()).step_by(size) {
+            let end = (start + size).min(units.len());
+            let mut complement = units[..start].to_vec();
+            complement.extend_from_slice(&units[end..]);
+            if fails(&complement)? {
+                units = complement;
+                chunks = (chunks - 1).max(2);
+                reduced = true;
+                break;
+            }
+        }
+        if !reduced {
+            if chunks >= units.len() {
+                break;
+            }
+            chunks = (chunks * 2).min(units.len());
+        }
+    }
+    Ok(units)
+}
+
+fn with_trailing_newline(mut s: String) -> String {
+    if !s.ends_with('\n') {
+        s.push('\n');
+    }
+    s
+}
+
+fn join_sections(sections: &[&str]) -> String {
+    with_trailing_newline(sections.join("\n\n"))
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    with_trailing_newline(lines.join("\n"))
+}
+
+/// Shrinks `input` while `fails` holds, removing blank-line separated sections, then lines, then
+/// comma separated items within lines, until none of them can be removed.
+fn shrink(input: &str, mut fails: impl FnMut(&str) -> io::Result<bool>) -> io::Result<String> {
+    let mut current = input.to_string();
+    loop {
+        let before = current.len();
+
+        let sections: Vec<&str> = current.trim_end_matches('\n').split("\n\n").collect();
+        let kept = ddmin(sections, |s| fails(&join_sections(s)))?;
+        current = join_sections(&kept);
+
+        let lines: Vec<&str> = current.lines().collect();
+        let kept = ddmin(lines, |l| fails(&join_lines(l)))?;
+        current = join_lines(&kept);
+
+        let mut lines: Vec<String> = current.lines().map(String::from).collect();
+        for i in 0..lines.len() {
+            let line = lines[i].clone();
+            let items: Vec<&str> = line.split(',').collect();
+            let kept = ddmin(items, |items| {
+                let mut candidate = lines.clone();
+                candidate[i] = items.join(",");
+                fails(&join_lines(
+                    &candidate.iter().map(String::as_str).collect::<Vec<_>>(),
+                ))
+            })?;
+            lines[i] = kept.join(",");
+        }
+        current = join_lines(&lines.iter().map(String::as_str).collect::<Vec<_>>());
+
+        if current.len() >= before {
+            return Ok(current);
+        }
+    }
+}
+
+/// Minimizes `input` for the first of `parts` that fails on it, writing the result to
+/// `minimized-part<N>.txt` in `dir`. Returns the file written, or `None` if no part fails.
+/// When `variant` has parameters, a part also fails if setting them changes its answer.
+pub fn minimize(
+    parts: &[usize],
+    input: &str,
+    timeout: Option<Duration>,
+    params: &[String],
+    variant: &[String],
+    dir: &Path,
+) -> parse::Result<Option<PathBuf>> {
+    let mut minimizer = Minimizer {
+        exe: std::env::current_exe()?,
+        part: 0,
+        timeout,
+        params: params.to_vec(),
+        variant: variant.to_vec(),
+        scratch: std::env::temp_dir().join(format!("advent-minimize-{}.txt", std::process::id())),
+        runs: 0,
+    };
+
+    let result = (|| {
+        for &part in parts {
+            minimizer.part = part;
+            let failure = match minimizer.run(input)? {
+                Some(failure) => failure,
+                None => continue,
+            };
+            eprintln!("Part {} fails with {:?}, minimizing", part, failure);
+
+            let minimized = shrink(input, |candidate| {
+                Ok(minimizer.run(candidate)?.as_ref() == Some(&failure))
+            })?;
+            eprintln!(
+                "Part {}: {} lines down to {} after {} runs",
+                part,
+                input.lines().count(),
+                minimized.lines().count(),
+                minimizer.runs
+            );
+
+            let path = dir.join(format!("minimized-part{}.txt", part));
+            fs::write(&path, minimized)?;
+            return Ok(Some(path));
+        }
+        Ok(None)
+    })();
+    let _ = fs::remove_file(&minimizer.scratch);
+    result
+}
+
+#[test]
+fn shrinks_to_failing_lines() {
+    let input = "1,2,3\n4,5,6\n\n7,8,9\n10,11,12\n";
+    let minimized =
+        shrink(input, |candidate| {
+            Ok(candidate.split([',', '\n']).any(|n| n == "5")
+                && candidate.contains("11"))
+        })
+        .unwrap();
+    assert_eq!(minimized, "5\n11\n");
+}
+
+#[test]
+fn failures_from_json() {
+    let result = |line| report_from_json(line).map(Report::into_result);
+    assert_eq!(
+        result(r#"{"part": 1, "panic": "no \"winner\"", "location": "four/src/main.rs:9:5"}"#),
+        Some(Err(Failure::Panic {
+            location: Some("four/src/main.rs:9:5".into())
+        }))
+    );
+    assert_eq!(
+        result(r#"{"part": 1, "panic": "no winner", "location": null}"#),
+        Some(Err(Failure::Panic { location: None }))
+    );
+    assert_eq!(
+        result(r#"{"part": 1, "timeout": 2, "progress": {"done": 1}}"#),
+        Some(Err(Failure::Timeout))
+    );
+    assert_eq!(
+        result(r#"{"part": 1, "answer": 4}"#),
+        Some(Ok(serde_json::json!(4)))
+    );
+    assert_eq!(result("Part 1: 4"), None);
+}
@@ -0,0 +1,212 @@
+//! Recognition of the block-letter fonts that some puzzles draw their answers in.
+
+use crate::Grid;
+
+type Glyph = (char, &'static [&'static str]);
+
+const FONT_6: &[Glyph] = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const FONT_10: &[Glyph] = &[
+    (
+        'A',
+        &[
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        &[
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        &[
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        &[
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        &[
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        &[
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        &[
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        &[
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        &[
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        &[
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        &[
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        &[
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+fn column_is_blank(grid: &Grid, x: usize, rows: &[usize]) -> bool {
+    rows.iter().all(|&y| !grid.get(x, y))
+}
+
+fn matches_glyph(grid: &Grid, rows: &[usize], columns: &[usize], glyph: &[&str]) -> bool {
+    let glyph_columns: Vec<usize> = (0..glyph[0].len())
+        .filter(|&x| glyph.iter().any(|row| row.as_bytes()[x] == b'#'))
+        .collect();
+    if glyph_columns.len() != columns.len() {
+        return false;
+    }
+
+    rows.iter().zip(glyph).all(|(&y, glyph_row)| {
+        columns
+            .iter()
+            .zip(&glyph_columns)
+            .all(|(&x, &gx)| grid.get(x, y) == (glyph_row.as_bytes()[gx] == b'#'))
+    })
+}
+
+/// Reads the letters drawn in the given grid, using either the 4x6 or 6x10 font depending on
+/// the height of the lit area. Returns `None` if anything in the grid isn't a known letter.
+pub fn recognize(grid: &Grid) -> Option<String> {
+    let rows: Vec<usize> = (0..grid.height())
+        .filter(|&y| (0..grid.width()).any(|x| grid.get(x, y)))
+        .collect();
+    let (&top, &bottom) = (rows.first()?, rows.last()?);
+    let rows: Vec<usize> = (top..=bottom).collect();
+
+    let font = match rows.len() {
+        6 => FONT_6,
+        10 => FONT_10,
+        _ => return None,
+    };
+
+    let mut letters = vec![];
+    let mut current = vec![];
+    for x in 0..=grid.width() {
+        if x < grid.width() && !column_is_blank(grid, x, &rows) {
+            current.push(x);
+        } else if !current.is_empty() {
+            letters.push(std::mem::take(&mut current));
+        }
+    }
+
+    letters
+        .iter()
+        .map(|columns| {
+            font.iter()
+                .find(|(_, glyph)| matches_glyph(grid, &rows, columns, glyph))
+                .map(|&(c, _)| c)
+        })
+        .collect()
+}
+
+#[test]
+fn recognize_letters() {
+    let picture = [
+        "...............................",
+        ".#..#.###..##..####.#......##..",
+        ".#..#..#..#..#.#....#.......#..",
+        ".####..#..#....###..#.......#..",
+        ".#..#..#..#.##.#....#.......#..",
+        ".#..#..#..#..#.#....#....#..#..",
+        ".#..#.###..###.####.####..##...",
+    ];
+    let grid = Grid::new(
+        picture
+            .iter()
+            .map(|r| r.chars().map(|c| c == '#').collect())
+            .collect(),
+    );
+    assert_eq!(recognize(&grid).as_deref(), Some("HIGELJ"));
+
+    let unknown = Grid::new(vec![vec![true; 3]; 3]);
+    assert_eq!(recognize(&unknown), None);
+}
@@ -0,0 +1,65 @@
+//! Named puzzle parameters, such as the number of days to simulate, that can be overridden
+//! without recompiling.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+struct Params {
+    overrides: BTreeMap<String, String>,
+    defaults: BTreeMap<String, String>,
+}
+
+static PARAMS: Mutex<Params> = Mutex::new(Params {
+    overrides: BTreeMap::new(),
+    defaults: BTreeMap::new(),
+});
+
+/// Returns the override for the named parameter if one is set and parses, otherwise `default`.
+/// A parameter has one default, so parts that default it differently name it per part, like
+/// `part_two.days`.
+pub fn param<T: FromStr + ToString>(name: &str, default: T) -> T {
+    // Released before panicking on a bad value, so that the other part can still read its own.
+    let value = {
+        let mut params = PARAMS.lock().unwrap();
+        params.defaults.insert(name.into(), default.to_string());
+        params.overrides.get(name).cloned()
+    };
+    match value.map(|v| (v.parse(), v)) {
+        Some((Ok(v), _)) => v,
+        Some((Err(_), v)) => panic!("invalid value {:?} for parameter `{}`", v, name),
+        None => default,
+    }
+}
+
+pub fn set_param(name: &str, value: &str) {
+    let mut params = PARAMS.lock().unwrap();
+    params.overrides.insert(name.into(), value.into());
+}
+
+pub fn clear_param(name: &str) {
+    PARAMS.lock().unwrap().overrides.remove(name);
+}
+
+/// Every parameter that has been looked up or overridden, with its default (if it has been
+/// looked up) and its override (if any).
+pub fn params() -> Vec<(String, Option<String>, Option<String>)> {
+    let params = PARAMS.lock().unwrap();
+    let mut names: Vec<&String> = params
+        .defaults
+        .keys()
+        .chain(params.overrides.keys())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|n| {
+            (
+                n.clone(),
+                params.defaults.get(n).cloned(),
+                params.overrides.get(n).cloned(),
+            )
+        })
+        .collect()
+}
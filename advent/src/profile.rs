@@ -0,0 +1,79 @@
+//! Sampling CPU profiler for `--profile`.
+
+use crate::harness::{run_part, Outcome, Part};
+use std::fs::File;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SAMPLE_FREQUENCY: i32 = 997;
+const MIN_DURATION: Duration = Duration::from_secs(5);
+
+fn profiler_error(e: pprof::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+fn write_collapsed(report: &pprof::Report, path: &Path) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    for (frames, count) in &report.data {
+        let mut line = frames.thread_name_or_id();
+        for frame in frames.frames.iter().rev() {
+            for symbol in frame.iter().rev() {
+                line.push(';');
+                line.push_str(&symbol.to_string());
+            }
+        }
+        writeln!(file, "{} {}", line, count)?;
+    }
+    Ok(())
+}
+
+/// Runs the part over and over under a sampling profiler, either `iterations` times or for a few
+/// seconds, then writes `profile-part<N>.svg` and `profile-part<N>.folded` into `dir`.
+///
+/// Returns the outcome of the last run, which stops the loop early if it panicked.
+pub fn profile_part(
+    number: usize,
+    part: Part,
+    input: &str,
+    dir: &Path,
+    iterations: Option<u32>,
+) -> parse::Result<(Outcome, Vec<PathBuf>)> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(SAMPLE_FREQUENCY)
+        .build()
+        .map_err(profiler_error)?;
+
+    let start = Instant::now();
+    let mut count = 0;
+    let outcome = loop {
+        let outcome = run_part(part, input)?;
+        count += 1;
+        let done = match iterations {
+            Some(n) => count >= n,
+            None => start.elapsed() >= MIN_DURATION,
+        };
+        if matches!(outcome, Outcome::Panicked(_)) || done {
+            break outcome;
+        }
+    };
+    let elapsed = start.elapsed();
+
+    let report = guard.report().build().map_err(profiler_error)?;
+    eprintln!(
+        "Part {}: {} iterations, {:?} per iteration",
+        number,
+        count,
+        elapsed / count
+    );
+
+    let svg = dir.join(format!("profile-part{}.svg", number));
+    report
+        .flamegraph(File::create(&svg)?)
+        .map_err(profiler_error)?;
+
+    let collapsed = dir.join(format!("profile-part{}.folded", number));
+    write_collapsed(&report, &collapsed)?;
+
+    Ok((outcome, vec![svg, collapsed]))
+}
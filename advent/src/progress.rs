@@ -0,0 +1,225 @@
+//! Progress reporting for long-running parts.
+//!
+//! A part either takes a `Progress` argument or calls `progress()` to get the handle for the
+//! thread it runs on, and then reports either `done` out of a known `total` or a free-form
+//! counter. The harness watches the handle from another thread, drawing a progress line on stderr
+//! and including the last value in timeout reports and JSON output.
+
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the progress line is redrawn.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Parts that finish sooner than this don't get a progress line at all.
+const DELAY: Duration = Duration::from_millis(500);
+
+/// Parts run on their own thread, which gets the same stack as the main thread would.
+const STACK_SIZE: usize = 8 << 20;
+
+const NO_TOTAL: u64 = u64::MAX;
+
+struct Shared {
+    reported: AtomicBool,
+    done: AtomicU64,
+    total: AtomicU64,
+    label: Mutex<Option<String>>,
+}
+
+/// A cheap, cloneable handle for reporting how far along a part is.
+#[derive(Clone)]
+pub struct Progress(Arc<Shared>);
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self(Arc::new(Shared {
+            reported: AtomicBool::new(false),
+            done: AtomicU64::new(0),
+            total: AtomicU64::new(NO_TOTAL),
+            label: Mutex::new(None),
+        }))
+    }
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the amount of work there is to do, turning the counter into `done/total`.
+    pub fn set_total(&self, total: u64) {
+        self.0.total.store(total, Ordering::Relaxed);
+        self.0.reported.store(true, Ordering::Relaxed);
+    }
+
+    /// Names what is being counted, such as "paths".
+    pub fn set_label(&self, label: &str) {
+        *self.0.label.lock().unwrap() = Some(label.into());
+        self.0.reported.store(true, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, done: u64) {
+        self.0.done.store(done, Ordering::Relaxed);
+        self.0.reported.store(true, Ordering::Relaxed);
+    }
+
+    pub fn add(&self, n: u64) {
+        self.0.done.fetch_add(n, Ordering::Relaxed);
+        self.0.reported.store(true, Ordering::Relaxed);
+    }
+
+    pub fn inc(&self) {
+        self.add(1)
+    }
+
+    /// The current value, or `None` if nothing has been reported.
+    pub fn snapshot(&self) -> Option<Snapshot> {
+        if !self.0.reported.load(Ordering::Relaxed) {
+            return None;
+        }
+        let total = self.0.total.load(Ordering::Relaxed);
+        Some(Snapshot {
+            done: self.0.done.load(Ordering::Relaxed),
+            total: (total != NO_TOTAL).then_some(total),
+            label: self.0.label.lock().unwrap().clone(),
+        })
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Progress> = RefCell::new(Progress::new());
+}
+
+/// The progress handle for the part running on this thread. Hot loops should get it once and
+/// keep it rather than calling this every iteration.
+pub fn progress() -> Progress {
+    CURRENT.with(|p| p.borrow().clone())
+}
+
+fn install(progress: Progress) {
+    CURRENT.with(|p| *p.borrow_mut() = progress);
+}
+
+/// The reported progress of a part at some point in time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Snapshot {
+    pub done: u64,
+    pub total: Option<u64>,
+    pub label: Option<String>,
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.done)?;
+        if let Some(total) = self.total {
+            write!(f, "/{}", total)?;
+        }
+        if let Some(label) = &self.label {
+            write!(f, " {}", label)?;
+        }
+        if let Some(total) = self.total.filter(|&t| t > 0) {
+            write!(f, " ({}%)", self.done.saturating_mul(100) / total)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn stderr_is_terminal() -> bool {
+    unsafe { libc::isatty(libc::STDERR_FILENO) == 1 }
+}
+
+#[cfg(not(unix))]
+fn stderr_is_terminal() -> bool {
+    false
+}
+
+/// Runs `f` on a new thread with a fresh progress handle, drawing a progress line for `name` on
+/// stderr while it runs, if stderr is a terminal.
+///
+/// Returns `None` if `f` is still running after `timeout`; the thread is left to run. Either way
+/// the last reported progress is returned too.
+pub(crate) fn watch<R: Send + 'static>(
+    name: &str,
+    timeout: Option<Duration>,
+    f: impl FnOnce() -> R + Send + 'static,
+) -> (Option<R>, Option<Snapshot>) {
+    let progress = Progress::new();
+    let (sender, receiver) = mpsc::channel();
+    let handle = {
+        let progress = progress.clone();
+        thread::Builder::new()
+            .name(name.into())
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                install(progress);
+                let _ = sender.send(f());
+            })
+            .expect("failed to spawn part thread")
+    };
+
+    let render = stderr_is_terminal();
+    let start = Instant::now();
+    let mut shown = None;
+    let result = loop {
+        let wait = timeout.map_or(TICK, |t| t.saturating_sub(start.elapsed()).min(TICK));
+        match receiver.recv_timeout(wait) {
+            Ok(r) => break Some(r),
+            Err(RecvTimeoutError::Disconnected) => match handle.join() {
+                Err(payload) => std::panic::resume_unwind(payload),
+                Ok(()) => unreachable!("part thread exited without a result"),
+            },
+            Err(RecvTimeoutError::Timeout) => {
+                if timeout.is_some_and(|t| start.elapsed() >= t) {
+                    break None;
+                }
+                let snapshot = progress.snapshot();
+                if let Some(s) = &snapshot {
+                    if render && start.elapsed() >= DELAY && snapshot != shown {
+                        eprint!("\r\x1b[K{}: {}", name, s);
+                        shown = snapshot;
+                    }
+                }
+            }
+        }
+    };
+    if shown.is_some() {
+        eprint!("\r\x1b[K");
+    }
+    (result, progress.snapshot())
+}
+
+#[test]
+fn display() {
+    let progress = Progress::new();
+    assert_eq!(progress.snapshot(), None);
+
+    progress.set_label("paths");
+    progress.add(41);
+    progress.inc();
+    assert_eq!(progress.snapshot().unwrap().to_string(), "42 paths");
+
+    progress.set_total(168);
+    let snapshot = progress.snapshot().unwrap();
+    assert_eq!(snapshot.to_string(), "42/168 paths (25%)");
+    assert_eq!(
+        serde_json::to_string(&snapshot).unwrap(),
+        r#"{"done":42,"total":168,"label":"paths"}"#
+    );
+}
+
+#[test]
+fn timeout() {
+    let (result, snapshot) = watch("test", Some(Duration::from_millis(200)), || {
+        progress().set(7);
+        thread::sleep(Duration::from_secs(60));
+    });
+    assert!(result.is_none());
+    assert_eq!(snapshot.unwrap().done, 7);
+}
@@ -0,0 +1,284 @@
+//! An interactive prompt over a parsed input, for `--repl`.
+
+use crate::harness::{run_isolated, OutputFormat, Part};
+use crate::params::{clear_param, params, set_param};
+use crate::Answer;
+use std::fmt;
+use std::io::{self, BufRead as _, Write as _};
+
+/// A part's input, parsed once and kept around so the part can be run on it repeatedly.
+pub struct Parsed {
+    debug: String,
+    run: Box<dyn FnMut() -> parse::Result<Answer>>,
+}
+
+impl Parsed {
+    pub fn new(debug: String, run: impl FnMut() -> parse::Result<Answer> + 'static) -> Self {
+        Self {
+            debug,
+            run: Box::new(run),
+        }
+    }
+}
+
+/// Wraps a parsed value for the generated code, which uses method resolution to pick the best
+/// available way to reuse and display it.
+pub struct Value<'a, T>(pub &'a T);
+
+pub trait ViaClone<T> {
+    fn reuse(&self) -> Option<T>;
+}
+
+impl<T: Clone> ViaClone<T> for Value<'_, T> {
+    fn reuse(&self) -> Option<T> {
+        Some(self.0.clone())
+    }
+}
+
+/// Types that aren't `Clone` have to be parsed again for each run.
+pub trait ViaReparse<T> {
+    fn reuse(&self) -> Option<T>;
+}
+
+impl<T> ViaReparse<T> for &Value<'_, T> {
+    fn reuse(&self) -> Option<T> {
+        None
+    }
+}
+
+pub trait ViaDebug {
+    fn debug(&self) -> String;
+}
+
+impl<T: fmt::Debug> ViaDebug for Value<'_, T> {
+    fn debug(&self) -> String {
+        format!("{:#?}", self.0)
+    }
+}
+
+pub trait ViaNoDebug {
+    fn debug(&self) -> String;
+}
+
+impl<T> ViaNoDebug for &Value<'_, T> {
+    fn debug(&self) -> String {
+        format!("<{} doesn't implement Debug>", std::any::type_name::<T>())
+    }
+}
+
+enum Piece {
+    Text(String),
+    Group(char, Vec<Vec<Piece>>),
+}
+
+fn closing(open: char) -> char {
+    match open {
+        '[' => ']',
+        '{' => '}',
+        _ => ')',
+    }
+}
+
+/// Splits `Debug` output into text and bracketed groups, stopping at `close` or a top-level
+/// comma.
+fn parse_pieces(chars: &mut std::iter::Peekable<std::str::Chars<'_>>, close: char) -> Vec<Piece> {
+    let mut pieces = vec![];
+    let mut text = String::new();
+    while let Some(&c) = chars.peek() {
+        if c == close || c == ',' {
+            break;
+        }
+        chars.next();
+        match c {
+            '[' | '{' | '(' => {
+                pieces.push(Piece::Text(std::mem::take(&mut text)));
+                let mut items = vec![];
+                loop {
+                    let item = parse_pieces(chars, closing(c));
+                    let empty = item
+                        .iter()
+                        .all(|p| matches!(p, Piece::Text(t) if t.trim().is_empty()));
+                    if !empty {
+                        items.push(item);
+                    }
+                    if chars.next() != Some(',') {
+                        break;
+                    }
+                }
+                pieces.push(Piece::Group(c, items));
+            }
+            '"' | '\'' => {
+                text.push(c);
+                while let Some(q) = chars.next() {
+                    text.push(q);
+                    if q == '\\' {
+                        text.extend(chars.next());
+                    } else if q == c {
+                        break;
+                    }
+                }
+            }
+            _ => text.push(c),
+        }
+    }
+    pieces.push(Piece::Text(text));
+    pieces
+}
+
+fn as_list(item: &[Piece]) -> Option<usize> {
+    let mut groups = item.iter().filter(|p| match p {
+        Piece::Text(t) => !t.trim().is_empty(),
+        Piece::Group(..) => true,
+    });
+    match (groups.next(), groups.next()) {
+        (Some(Piece::Group('[', items)), None) => Some(items.len()),
+        _ => None,
+    }
+}
+
+fn summarize_pieces(pieces: &[Piece], out: &mut String) {
+    for piece in pieces {
+        match piece {
+            Piece::Text(t) => out.push_str(t.trim()),
+            Piece::Group('[', items) => {
+                let rows: Option<Vec<usize>> = items.iter().map(|i| as_list(i)).collect();
+                match rows {
+                    Some(rows) if !rows.is_empty() && rows.iter().all(|&r| r == rows[0]) => {
+                        out.push_str(&format!("[{}x{} grid]", rows[0], rows.len()))
+                    }
+                    _ => out.push_str(&format!("[{} items]", items.len())),
+                }
+            }
+            Piece::Group('{', items) if !out.ends_with(|c: char| c.is_alphanumeric()) => {
+                out.push_str(&format!("{{{} entries}}", items.len()))
+            }
+            Piece::Group(open, items) => {
+                if *open == '{' {
+                    out.push(' ');
+                }
+                out.push(*open);
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    } else if *open == '{' {
+                        out.push(' ');
+                    }
+                    summarize_pieces(item, out);
+                }
+                if *open == '{' {
+                    out.push(' ');
+                }
+                out.push(closing(*open));
+            }
+        }
+        if out.ends_with(':') {
+            out.push(' ');
+        }
+    }
+}
+
+/// Condenses `Debug` output by replacing every list with its length, or its dimensions when it
+/// is a list of equally long lists, and every map or set with its size.
+pub fn summarize(debug: &str) -> String {
+    let mut out = String::new();
+    summarize_pieces(&parse_pieces(&mut debug.chars().peekable(), '\0'), &mut out);
+    out
+}
+
+const HELP: &str = "\
+commands:
+  run [PART]          run one or both parts on the parsed input
+  debug [PART]        print the parsed input
+  summary [PART]      print the shape of the parsed input
+  set NAME VALUE      override a parameter
+  unset NAME          go back to the parameter's default
+  params              list parameters
+  help                show this message
+  quit";
+
+fn selected<'a>(
+    parsed: &'a mut [Parsed],
+    arg: Option<&str>,
+) -> Option<Vec<(usize, &'a mut Parsed)>> {
+    let all = (1..).zip(parsed.iter_mut());
+    match arg {
+        None => Some(all.collect()),
+        Some(n) => {
+            let n: usize = n.parse().ok()?;
+            let selected: Vec<_> = all.filter(|(i, _)| *i == n).collect();
+            (!selected.is_empty()).then_some(selected)
+        }
+    }
+}
+
+/// Parses the input for every part and then reads commands from stdin until it is closed.
+pub fn run(parts: &[Part], input: &str) -> parse::Result<()> {
+    let mut parsed = parts
+        .iter()
+        .map(|p| (p.repl)(input))
+        .collect::<parse::Result<Vec<_>>>()?;
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match &words[..] {
+            [] => {}
+            ["quit" | "exit"] => break,
+            ["help"] => println!("{}", HELP),
+            ["params"] => {
+                for (name, default, value) in params() {
+                    let default = default.unwrap_or_else(|| "?".into());
+                    match value {
+                        Some(v) => println!("{} = {} (default {})", name, v, default),
+                        None => println!("{} = {}", name, default),
+                    }
+                }
+            }
+            ["set", name, value] => set_param(name, value),
+            ["unset", name] => clear_param(name),
+            [command @ ("run" | "debug" | "summary"), rest @ ..] if rest.len() <= 1 => {
+                let parts = match selected(&mut parsed, rest.first().copied()) {
+                    Some(parts) => parts,
+                    None => {
+                        println!("no such part");
+                        continue;
+                    }
+                };
+                for (number, p) in parts {
+                    match *command {
+                        "run" => {
+                            let outcome = run_isolated(&mut p.run);
+                            match outcome {
+                                Ok(outcome) => OutputFormat::Text.report(number, &outcome, None),
+                                Err(e) => println!("Part {}: {}", number, e),
+                            }
+                        }
+                        "debug" => println!("Part {}:\n{}", number, p.debug),
+                        _ => println!("Part {}: {}", number, summarize(&p.debug)),
+                    }
+                }
+            }
+            _ => println!("unknown command, try `help`"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn summary() {
+    assert_eq!(
+        summarize("Floor { grid: [[1, 2, 3], [4, 5, 6]], name: \"a, [b\" }"),
+        "Floor { grid: [3x2 grid], name: \"a, [b\" }"
+    );
+    assert_eq!(
+        summarize("(List([1, 2, 3], PhantomData<parse::Comma>), {'a': 1, 'b': 2})"),
+        "(List([3 items], PhantomData<parse::Comma>), {2 entries})"
+    );
+}
@@ -0,0 +1,178 @@
+//! Snapshot tests, for outputs that are easier to check by looking at them than by writing them
+//! out by hand.
+//!
+//! `assert_snapshot!("name", value)` compares the value's `Display` output, and
+//! `assert_debug_snapshot!` its pretty `Debug` output, against `snapshots/name.snap` in the calling
+//! crate. Run the tests with `ADVENT_UPDATE_SNAPSHOTS=1` to write new or changed snapshots instead
+//! of failing.
+
+use std::fs;
+use std::path::Path;
+
+pub const UPDATE_VAR: &str = "ADVENT_UPDATE_SNAPSHOTS";
+
+/// Lines of unchanged text shown around each change in a diff.
+const CONTEXT: usize = 2;
+
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $value:expr) => {
+        $crate::snapshot::assert_matches(
+            env!("CARGO_MANIFEST_DIR"),
+            $name,
+            &::std::format!("{}", $value),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! assert_debug_snapshot {
+    ($name:expr, $value:expr) => {
+        $crate::snapshot::assert_matches(
+            env!("CARGO_MANIFEST_DIR"),
+            $name,
+            &::std::format!("{:#?}", $value),
+        )
+    };
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff from `old` to `new` via their longest common subsequence.
+fn diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = vec![];
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] > lcs[i + 1][j]) {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        } else {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        }
+    }
+    lines
+}
+
+/// Renders a diff with `-` for expected lines and `+` for actual ones, eliding unchanged lines
+/// far from any change.
+fn render_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    let lines = diff(&expected, &actual);
+
+    let changed: Vec<usize> = (0..lines.len())
+        .filter(|&i| !matches!(lines[i], Line::Same(_)))
+        .collect();
+    let near_change = |i: usize| {
+        changed
+            .iter()
+            .any(|&c| c.saturating_sub(CONTEXT) <= i && i <= c + CONTEXT)
+    };
+
+    let mut out = String::new();
+    let mut elided = false;
+    for (i, line) in lines.iter().enumerate() {
+        if !near_change(i) {
+            if !elided {
+                out.push_str("  ...\n");
+                elided = true;
+            }
+            continue;
+        }
+        elided = false;
+        let (sign, text) = match line {
+            Line::Same(t) => (' ', t),
+            Line::Removed(t) => ('-', t),
+            Line::Added(t) => ('+', t),
+        };
+        out.push_str(&format!("{} {}\n", sign, text));
+    }
+    out
+}
+
+fn updating() -> bool {
+    std::env::var_os(UPDATE_VAR).is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Compares `actual` against the snapshot called `name` in `<manifest_dir>/snapshots`, panicking
+/// with a diff if it differs, or writing it if updates were asked for. Used by
+/// `assert_snapshot!` and `assert_debug_snapshot!`.
+#[track_caller]
+pub fn assert_matches(manifest_dir: &str, name: &str, actual: &str) {
+    assert!(
+        !name.is_empty() && !name.contains(|c: char| c == '/' || c == '\\' || c.is_whitespace()),
+        "invalid snapshot name {:?}",
+        name
+    );
+    let dir = Path::new(manifest_dir).join("snapshots");
+    let path = dir.join(format!("{}.snap", name));
+
+    let mut actual = actual.to_string();
+    if !actual.ends_with('\n') {
+        actual.push('\n');
+    }
+
+    let expected = fs::read_to_string(&path).ok();
+    if expected.as_deref() == Some(actual.as_str()) {
+        return;
+    }
+
+    if updating() {
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, &actual).unwrap();
+        eprintln!("updated snapshot {}", path.display());
+        return;
+    }
+
+    match expected {
+        Some(expected) => panic!(
+            "snapshot `{}` doesn't match {}:\n{}set {}=1 to accept the new value",
+            name,
+            path.display(),
+            render_diff(&expected, &actual),
+            UPDATE_VAR
+        ),
+        None => panic!(
+            "no snapshot `{}` at {}, the value is:\n{}set {}=1 to create it",
+            name,
+            path.display(),
+            actual,
+            UPDATE_VAR
+        ),
+    }
+}
+
+#[test]
+fn line_diff() {
+    let expected = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    let actual = "a\nb\nc\nd\nE\nf\ng\nh\n";
+    assert_eq!(
+        render_diff(expected, actual),
+        "  ...\n  c\n  d\n- e\n+ E\n  f\n  g\n  ...\n"
+    );
+    assert_eq!(
+        diff(&["x", "y"], &["y", "z"]),
+        vec![Line::Removed("x"), Line::Same("y"), Line::Added("z")]
+    );
+}
@@ -6,45 +6,219 @@ use quote::quote;
 use syn::spanned::Spanned as _;
 use syn::*;
 
-fn part_inner(input: TokenStream, part_number: usize) -> Result<File> {
+/// Parts with several arguments parse them from `Sections`, which goes up to this many.
+const MAX_SECTIONS: usize = 5;
+
+/// Whether the argument is the part's progress handle, `Progress` or `&Progress`, rather than
+/// something parsed from the input.
+fn progress_arg(ty: &Type) -> Option<proc_macro2::TokenStream> {
+    let (ty, by_ref) = match ty {
+        Type::Reference(r) => (&*r.elem, true),
+        ty => (ty, false),
+    };
+    match ty {
+        Type::Path(p) if p.path.segments.last()?.ident == "Progress" => Some(if by_ref {
+            quote!(&::advent::progress())
+        } else {
+            quote!(::advent::progress())
+        }),
+        _ => None,
+    }
+}
+
+/// Whether the part's arguments borrow from the input, which `#[part_one(borrowed)]` says.
+/// Borrowed arguments are parsed with `HasBorrowedParser`, and parsed again for every run since
+/// they can't outlive the input.
+fn is_borrowed(attr: TokenStream) -> Result<bool> {
+    if attr.is_empty() {
+        return Ok(false);
+    }
+    let ident: Ident = parse(attr)?;
+    if ident == "borrowed" {
+        Ok(true)
+    } else {
+        Err(Error::new(ident.span(), "expected `borrowed`"))
+    }
+}
+
+fn part_inner(attr: TokenStream, input: TokenStream, part_number: usize) -> Result<File> {
+    let borrowed = is_borrowed(attr)?;
     let func: ItemFn = parse(input)?;
 
     let func_name = func.sig.ident.clone();
     let args = func.sig.inputs.clone();
 
-    if args.len() != 1 {
-        return Err(Error::new(args.span(), "too many inputs"));
+    let mut parsed_types = vec![];
+    let mut call_args = vec![];
+    for arg in args.iter() {
+        if let FnArg::Typed(pat_type) = arg {
+            if let Some(progress) = progress_arg(&pat_type.ty) {
+                call_args.push(progress);
+                continue;
+            }
+            let var = Ident::new(&format!("p{}", parsed_types.len()), Span::call_site());
+            call_args.push(quote!(#var));
+            parsed_types.push((*pat_type.ty).clone());
+        } else {
+            return Err(Error::new(arg.span(), "invalid input type"));
+        }
     }
-    let parsed_type = if let FnArg::Typed(pat_type) = args.first().unwrap() {
-        (&*pat_type.ty).clone()
+    if parsed_types.is_empty() {
+        return Err(Error::new(func.sig.span(), "missing input"));
+    }
+
+    let ident = |prefix: &str| Ident::new(&format!("{}{}", prefix, part_number), Span::call_site());
+    let parse_fn = ident("_parse_part_");
+    let tramp = ident("_run_part_");
+    let repl_fn = ident("_repl_part_");
+    let part_const = ident("_PART_");
+    let hot_run = ident("_hot_run_part_");
+    let hot_layout = ident("_hot_layout_part_");
+    let hot_parse = ident("_hot_parse_part_");
+    let hot_part = ident("_HOT_PART_");
+
+    let vars: Vec<_> = (0..parsed_types.len())
+        .map(|i| Ident::new(&format!("p{}", i), Span::call_site()))
+        .collect();
+
+    let (parsed_type, pattern, parse_body) = if let [parsed_type] = &parsed_types[..] {
+        let parse = if borrowed {
+            quote!(::advent::parse::parse_borrowed(input))
+        } else {
+            quote! {{
+                use ::advent::harness::{ViaLines as _, ViaParser as _};
+                let whole = ::advent::harness::WholeInput::<#parsed_type>(::std::marker::PhantomData);
+                (&whole).parse(input)
+            }}
+        };
+        (quote!(#parsed_type), quote!(p0), parse)
     } else {
-        return Err(Error::new(args.span(), "invalid input type"));
+        if parsed_types.len() > MAX_SECTIONS {
+            return Err(Error::new(
+                func.sig.inputs.span(),
+                format!("at most {} input sections are supported", MAX_SECTIONS),
+            ));
+        }
+        let parse = if borrowed {
+            quote!(parse_borrowed)
+        } else {
+            quote!(parse_str)
+        };
+        let parsed_type = quote!((#(#parsed_types),*));
+        (
+            parsed_type.clone(),
+            quote!((#(#vars),*)),
+            quote! {
+                ::advent::parse::#parse::<::advent::parse::Sections<#parsed_type>>(input)
+                    .map(|sections| sections.0)
+            },
+        )
     };
 
-    let tramp = Ident::new(&format!("_run_part_{}", part_number), Span::call_site());
+    // The REPL keeps the parsed value to run the part on again, unless it borrows from the input.
+    let repl_value = if borrowed {
+        quote!(#parse_fn(&input)?)
+    } else {
+        quote! {{
+            use ::advent::repl::{ViaClone as _, ViaReparse as _};
+            match (&::advent::repl::Value(&value)).reuse() {
+                Some(v) => v,
+                None => #parse_fn(&input)?,
+            }
+        }}
+    };
+    // The `hot` host keeps the parsed value across reloads, unless it borrows from the input.
+    let hot = if borrowed {
+        quote! {
+            unsafe fn #hot_run(
+                _: Option<&::advent::hot::Kept>,
+                input: &str,
+            ) -> ::advent::parse::Result<::advent::harness::Outcome> {
+                ::advent::harness::run_part(#part_const, input)
+            }
+
+            const #hot_part: ::advent::hot::HotPart = ::advent::hot::HotPart {
+                keep: None,
+                run: #hot_run,
+            };
+        }
+    } else {
+        quote! {
+            fn #hot_layout() -> ::advent::hot::Layout {
+                ::advent::hot::Layout::of::<#parsed_type>()
+            }
 
+            fn #hot_parse(input: &str) -> ::advent::parse::Result<::advent::hot::Kept> {
+                #parse_fn(input).map(::advent::hot::Kept::new)
+            }
+
+            unsafe fn #hot_run(
+                kept: Option<&::advent::hot::Kept>,
+                input: &str,
+            ) -> ::advent::parse::Result<::advent::harness::Outcome> {
+                use ::advent::repl::{ViaClone as _, ViaReparse as _};
+                let value = kept.map(|k| k.get::<#parsed_type>());
+                ::advent::harness::run_isolated(|| {
+                    let #pattern = match value.and_then(|v| (&::advent::repl::Value(v)).reuse()) {
+                        Some(v) => v,
+                        None => #parse_fn(input)?,
+                    };
+                    Ok(#func_name(#(#call_args),*).into())
+                })
+            }
+
+            const #hot_part: ::advent::hot::HotPart = ::advent::hot::HotPart {
+                keep: Some(::advent::hot::Keep {
+                    layout: #hot_layout,
+                    parse: #hot_parse,
+                }),
+                run: #hot_run,
+            };
+        }
+    };
     Ok(parse_quote! {
         #func
-        fn #tramp(input: &str) -> ::advent::parse::Result<()> {
-            let p: #parsed_type = ::advent::parse::parse_str(input)?;
-            let result = #func_name(p);
-            println!("Part {}: {}", #part_number, result);
-            Ok(())
+
+        fn #parse_fn(input: &str) -> ::advent::parse::Result<#parsed_type> {
+            #parse_body
+        }
+
+        fn #tramp(input: &str) -> ::advent::parse::Result<::advent::Answer> {
+            let #pattern = #parse_fn(input)?;
+            Ok(#func_name(#(#call_args),*).into())
+        }
+
+        fn #repl_fn(input: &str) -> ::advent::parse::Result<::advent::repl::Parsed> {
+            use ::advent::repl::{ViaDebug as _, ViaNoDebug as _};
+            let value = #parse_fn(input)?;
+            let debug = (&::advent::repl::Value(&value)).debug();
+            let input = input.to_string();
+            Ok(::advent::repl::Parsed::new(debug, move || {
+                let #pattern = #repl_value;
+                Ok(#func_name(#(#call_args),*).into())
+            }))
         }
+
+        const #part_const: ::advent::harness::Part = ::advent::harness::Part {
+            run: #tramp,
+            repl: #repl_fn,
+        };
+
+        #hot
     })
 }
 
 #[proc_macro_attribute]
-pub fn part_one(_attr: TokenStream, input: TokenStream) -> TokenStream {
-    match part_inner(input, 1) {
+pub fn part_one(attr: TokenStream, input: TokenStream) -> TokenStream {
+    match part_inner(attr, input, 1) {
         Ok(v) => quote!(#v).into(),
         Err(e) => e.into_compile_error().into(),
     }
 }
 
 #[proc_macro_attribute]
-pub fn part_two(_attr: TokenStream, input: TokenStream) -> TokenStream {
-    match part_inner(input, 2) {
+pub fn part_two(attr: TokenStream, input: TokenStream) -> TokenStream {
+    match part_inner(attr, input, 2) {
         Ok(v) => quote!(#v).into(),
         Err(e) => e.into_compile_error().into(),
     }
@@ -53,15 +227,18 @@ pub fn part_two(_attr: TokenStream, input: TokenStream) -> TokenStream {
 #[proc_macro]
 pub fn harness(_attr: TokenStream) -> TokenStream {
     quote! {
-        fn main() -> ::advent::parse::Result<()> {
-            use ::std::io::Read as _;
-            let mut input = ::std::string::String::new();
-            ::std::io::stdin().lock().read_to_string(&mut input)?;
+        static _PARTS: [::advent::harness::Part; 2] = [_PART_1, _PART_2];
 
-            _run_part_1(&input)?;
-            _run_part_2(&input)?;
+        // Unused when the day is built as a library for the `hot` host.
+        #[allow(dead_code)]
+        fn main() {
+            ::advent::harness::main(env!("CARGO_MANIFEST_DIR"), &_PARTS)
+        }
 
-            Ok(())
+        #[no_mangle]
+        pub fn _advent_hot_parts() -> &'static [::advent::hot::HotPart] {
+            static HOT_PARTS: [::advent::hot::HotPart; 2] = [_HOT_PART_1, _HOT_PART_2];
+            &HOT_PARTS
         }
     }
     .into()
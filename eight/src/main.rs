@@ -1,5 +1,5 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 use enum_iterator::IntoEnumIterator;
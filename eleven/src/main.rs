@@ -1,5 +1,5 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 use enum_iterator::IntoEnumIterator;
@@ -116,7 +116,7 @@ impl Cavern {
 impl HasParser for Cavern {
     #[into_parser]
     fn parser() -> _ {
-        let line = many1(digit().map(|d| d.to_string().parse::<u8>().unwrap()));
+        let line = many1(digit_value::<_, u8>());
         many1(line.skip(token('\n'))).map(|grid| Self {
             grid,
             total_flashes: 0,
@@ -126,7 +126,7 @@ impl HasParser for Cavern {
 
 #[part_one]
 fn part_one(mut cavern: Cavern) -> u64 {
-    for _ in 0..100 {
+    for _ in 0..param("steps", 100) {
         cavern.simulate();
     }
     cavern.total_flashes
@@ -144,4 +144,15 @@ fn part_two(mut cavern: Cavern) -> u64 {
     }
 }
 
+#[test]
+fn example_steps() {
+    let mut cavern: Cavern = parse_str(include_str!("../example2.txt")).unwrap();
+    let mut steps = String::new();
+    for step in 1..=10 {
+        cavern.simulate();
+        steps += &format!("After step {}:\n{:?}\n", step, cavern);
+    }
+    advent::assert_snapshot!("example_steps", steps);
+}
+
 harness!();
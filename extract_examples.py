@@ -0,0 +1,130 @@
+#!/usr/bin/env python3
+
+import argparse
+import os
+import sys
+
+from html.parser import HTMLParser
+
+EXPECTED_TOML = 'expected.toml'
+
+
+class PuzzlePage(HTMLParser):
+    '''Collects the example blocks and emphasized answers of each part of a saved puzzle page.
+
+    Each part is an `<article class="day-desc">`. Example blocks are `<pre><code>` and answers are
+    code that is entirely emphasized, either `<code><em>..</em></code>` or `<em><code>..</code></em>`.
+    '''
+
+    def __init__(self):
+        super().__init__()
+        self.parts = []
+        self.tags = []
+        self.code = None
+        self.em = None
+        self.code_in_em = False
+        self.em_in_code = None
+
+    def in_tag(self, tag):
+        return tag in self.tags
+
+    def handle_starttag(self, tag, attrs):
+        self.tags.append(tag)
+        if tag == 'article' and 'day-desc' in (dict(attrs).get('class') or ''):
+            self.parts.append({'examples': [], 'answers': []})
+        elif tag == 'code':
+            self.code = ''
+            self.code_in_em = self.em is not None
+            self.em_in_code = None
+        elif tag == 'em':
+            self.em = ''
+
+    def handle_endtag(self, tag):
+        while self.tags and self.tags.pop() != tag:
+            pass
+        if not self.parts:
+            return
+        part = self.parts[-1]
+        if tag == 'code' and self.code is not None:
+            if self.in_tag('pre'):
+                part['examples'].append(self.code)
+            elif self.code_in_em or self.em_in_code == self.code:
+                part['answers'].append(self.code.strip())
+            self.code = None
+        elif tag == 'em':
+            if self.code is not None:
+                self.em_in_code = self.em
+            self.em = None
+
+    def handle_data(self, data):
+        if self.code is not None:
+            self.code += data
+        if self.em is not None:
+            self.em += data
+
+
+def toml_string(s: str) -> str:
+    return '"' + s.replace('\\', '\\\\').replace('"', '\\"') + '"'
+
+
+def extract(name: str, page: str, force: bool) -> int:
+    parser = PuzzlePage()
+    with open(page) as f:
+        parser.feed(f.read())
+
+    if not parser.parts:
+        print(f"ERROR: no puzzle description found in {page}")
+        return 1
+
+    examples = []
+    for part in parser.parts:
+        for example in part['examples']:
+            if example not in examples:
+                examples.append(example)
+
+    if not examples:
+        print(f"ERROR: no example blocks found in {page}")
+        return 1
+
+    paths = [os.path.join(name, f'example{n}.txt') for n in range(len(examples))]
+    paths.append(os.path.join(name, EXPECTED_TOML))
+    existing = [p for p in paths if os.path.exists(p)]
+    if existing and not force:
+        print(f"ERROR: {', '.join(existing)} already exist, use --force to overwrite")
+        return 1
+
+    for path, example in zip(paths, examples):
+        with open(path, 'w') as f:
+            f.write(example)
+        print(f"wrote {path}")
+
+    # The first block is almost always the example input, and the last emphasized answer in each
+    # part is almost always that part's answer for it. The other emphasized values are listed so
+    # that they are easy to move around when the guess is wrong.
+    lines = ['[example0]']
+    for n, part in enumerate(parser.parts, 1):
+        answers = part['answers']
+        if answers:
+            lines.append(f'part{n} = {toml_string(answers[-1])}')
+    for n, part in enumerate(parser.parts, 1):
+        others = part['answers'][:-1]
+        if others:
+            lines.append(f'# other emphasized values in part {n}: {", ".join(others)}')
+    with open(os.path.join(name, EXPECTED_TOML), 'w') as f:
+        f.write('\n'.join(lines) + '\n')
+    print(f"wrote {os.path.join(name, EXPECTED_TOML)}")
+    return 0
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(
+        description="Extract examples and expected answers from a saved puzzle page")
+    parser.add_argument("name")
+    parser.add_argument("page", help="puzzle page saved as HTML")
+    parser.add_argument("--force", action="store_true", help="overwrite existing files")
+    args = parser.parse_args()
+    return extract(args.name, args.page, args.force)
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -1,5 +1,5 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 use enum_iterator::IntoEnumIterator;
@@ -15,8 +15,7 @@ struct Board {
 impl HasParser for Board {
     #[into_parser]
     fn parser() -> _ {
-        many1(many1(digit().map(|d| d.to_string().parse::<u64>().unwrap())).skip(token('\n')))
-            .map(|grid| Self { grid, scale: 1 })
+        many1(many1(digit_value::<_, u64>()).skip(token('\n'))).map(|grid| Self { grid, scale: 1 })
     }
 }
 
@@ -82,6 +81,9 @@ fn min_path_cost(b: &Board) -> u64 {
     let mut heap = BinaryHeap::new();
     let mut dist = vec![vec![u64::MAX; b.width()]; b.height()];
 
+    let progress = progress();
+    progress.set_total((b.width() * b.height()) as u64);
+
     heap.push(HeapNode((endx, endy), 0));
 
     while let Some(HeapNode((x, y), mut cost)) = heap.pop() {
@@ -92,6 +94,7 @@ fn min_path_cost(b: &Board) -> u64 {
         if cost > dist[y][x] {
             continue;
         }
+        progress.inc();
 
         cost += b.get(x, y);
         for (nx, ny) in b.all_adjacent(x, y) {
@@ -1,5 +1,5 @@
-#![feature(generic_associated_types)]
-#![feature(type_alias_impl_trait)]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
 
 use advent::prelude::*;
 use std::collections::HashMap;
@@ -13,10 +13,7 @@ struct Position {
 impl HasParser for Position {
     #[into_parser]
     fn parser() -> _ {
-        (u32::parser().skip(char(',')), u32::parser()).map(|(x, y)| Self {
-            x: x as i32,
-            y: y as i32,
-        })
+        (i32::parser().skip(char(',')), i32::parser()).map(|(x, y)| Self { x, y })
     }
 }
 
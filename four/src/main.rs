@@ -1,5 +1,5 @@
-#![feature(generic_associated_types)]
-#![feature(type_alias_impl_trait)]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
 
 use advent::prelude::*;
 
@@ -24,7 +24,7 @@ struct BingoBoard {
 }
 
 impl BingoBoard {
-    fn new(values: Vec<Vec<u32>>) -> Self {
+    fn new(values: Vec<List<u32, Whitespace, Exactly<5>>>) -> Self {
         Self {
             board: values
                 .into_iter()
@@ -79,10 +79,10 @@ impl BingoBoard {
 impl HasParser for BingoBoard {
     #[into_parser]
     fn parser() -> _ {
-        let spaces = many::<String, _, _>(token(' '));
-        let spaces1 = many1::<String, _, _>(token(' '));
-        let one_line = sep_by1(spaces.with(u32::parser()), spaces1).skip(newline());
-        many1(one_line).map(Self::new)
+        let one_line = skip_many(token(' '))
+            .with(List::parser())
+            .skip(NewLine::parser());
+        count_min_max(5, 5, one_line).map(Self::new)
     }
 }
 
@@ -95,9 +95,8 @@ struct BingoGame {
 impl HasParser for BingoGame {
     #[into_parser]
     fn parser() -> _ {
-        let input = List::<u32, Comma>::parser().skip(newline());
-        let boards = sep_by1(BingoBoard::parser(), newline());
-        (input.skip(newline()), boards).map(|(input, boards)| Self { input, boards })
+        Sections::<(List<u32, Comma>, Sections<Vec<BingoBoard>>)>::parser()
+            .map(|Sections((input, Sections(boards)))| Self { input, boards })
     }
 }
 
@@ -1,5 +1,5 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 use std::collections::HashMap;
@@ -19,18 +19,13 @@ impl HasParser for Rule {
     }
 }
 
-#[derive(Debug)]
-struct Input {
-    template: String,
-    rules: List<Rule, NewLine>,
-}
+/// The starting polymer, which ends at the end of its line.
+struct Template(Vec<char>);
 
-impl HasParser for Input {
+impl HasParser for Template {
     #[into_parser]
     fn parser() -> _ {
-        let template = many1(upper()).skip(string("\n\n"));
-        (template, List::<Rule, NewLine>::parser())
-            .map(|(template, rules)| Self { template, rules })
+        many1(upper()).map(Self)
     }
 }
 
@@ -67,10 +62,10 @@ fn apply_rules(pair: [char; 2], rules: &HashMap<[char; 2], char>, depth: u64) ->
     stats
 }
 
-fn run_polymer_steps(i: Input, n: u64) -> u64 {
-    let rules: HashMap<_, _> = i.rules.into_iter().map(|r| (r.src, r.dst)).collect();
+fn run_polymer_steps(template: Template, rules: List<Rule, NewLine>, n: u64) -> u64 {
+    let rules: HashMap<_, _> = rules.into_iter().map(|r| (r.src, r.dst)).collect();
     let mut stats = Stats::new();
-    let cs: Vec<_> = i.template.chars().collect();
+    let cs = template.0;
     for pair in cs.windows(2) {
         stats.union(&apply_rules(pair.try_into().unwrap(), &rules, n));
     }
@@ -82,13 +77,13 @@ fn run_polymer_steps(i: Input, n: u64) -> u64 {
 }
 
 #[part_one]
-fn part_one(i: Input) -> u64 {
-    run_polymer_steps(i, 10)
+fn part_one(template: Template, rules: List<Rule, NewLine>) -> u64 {
+    run_polymer_steps(template, rules, param("part_one.steps", 10))
 }
 
 #[part_two]
-fn part_two(i: Input) -> u64 {
-    run_polymer_steps(i, 40)
+fn part_two(template: Template, rules: List<Rule, NewLine>) -> u64 {
+    run_polymer_steps(template, rules, param("part_two.steps", 40))
 }
 
 harness!();
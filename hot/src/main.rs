@@ -0,0 +1,323 @@
+//! Development host that keeps a day's parsed input in memory while its solution is rebuilt.
+//!
+//! The day's `src/main.rs` is built as a `cdylib` under `target/hot`. Whenever a file in the day's
+//! `src` directory changes, the library is rebuilt and loaded again and the parts are re-run on the
+//! values parsed from the input before. A day whose parsed types are its own can keep them and
+//! their parsers in `src/lib.rs`, which `main.rs` uses as a crate, so that editing the parts
+//! doesn't mean parsing again.
+
+use advent::harness::Outcome;
+use advent::hot::{HotPart, Kept, PartsFn, PARTS_SYMBOL};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash as _, Hasher as _};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::{Duration, Instant, SystemTime};
+use std::{env, fs, io, thread};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+fn usage() -> ! {
+    eprintln!("usage: {} DAY [--input PATH]", env::args().next().unwrap());
+    process::exit(2)
+}
+
+struct Day {
+    name: String,
+    root: PathBuf,
+    dir: PathBuf,
+    target_dir: PathBuf,
+}
+
+impl Day {
+    fn new(name: String) -> Self {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        Self {
+            dir: root.join(&name),
+            target_dir: root.join("target/hot"),
+            root: root.into(),
+            name,
+        }
+    }
+
+    fn lib_name(&self) -> String {
+        format!("{}_hot", self.name)
+    }
+
+    /// Writes a manifest that builds the day's `main.rs` as a `cdylib` with the day's
+    /// dependencies and features, with path dependencies made absolute, plus the day's own
+    /// library if it has one. The workspace's lock file is copied along with it the first time, so
+    /// that the same versions get picked where possible.
+    fn write_manifest(&self) -> io::Result<PathBuf> {
+        let invalid = |e: toml::de::Error| io::Error::new(io::ErrorKind::InvalidData, e);
+        let day_manifest: toml::Table = fs::read_to_string(self.dir.join("Cargo.toml"))?
+            .parse()
+            .map_err(invalid)?;
+
+        let mut dependencies = match day_manifest.get("dependencies") {
+            Some(toml::Value::Table(t)) => t.clone(),
+            _ => toml::Table::new(),
+        };
+        for (_, dependency) in dependencies.iter_mut() {
+            if let Some(toml::Value::String(path)) = dependency.get_mut("path") {
+                let absolute = self.dir.join(path.as_str());
+                *path = absolute.to_string_lossy().into_owned();
+            }
+        }
+
+        let mut manifest: toml::Table = format!(
+            "[package]\n\
+             name = {:?}\n\
+             version = \"0.1.0\"\n\
+             edition = \"2021\"\n\
+             \n\
+             [lib]\n\
+             path = {:?}\n\
+             crate-type = [\"cdylib\"]\n\
+             \n\
+             [workspace]\n",
+            self.lib_name(),
+            self.dir.join("src/main.rs").to_string_lossy(),
+        )
+        .parse()
+        .map_err(invalid)?;
+        let mut features = match day_manifest.get("features") {
+            Some(toml::Value::Table(t)) => t.clone(),
+            _ => toml::Table::new(),
+        };
+        if self.dir.join("src/lib.rs").exists() {
+            let mut library = toml::Table::new();
+            library.insert(
+                "path".into(),
+                self.dir.to_string_lossy().into_owned().into(),
+            );
+            dependencies.insert(self.name.clone(), library.into());
+            for (feature, enables) in features.iter_mut() {
+                if let toml::Value::Array(enables) = enables {
+                    enables.push(format!("{}/{}", self.name, feature).into());
+                }
+            }
+        }
+        manifest.insert("dependencies".into(), dependencies.into());
+        manifest.insert("features".into(), features.into());
+
+        let dir = self.target_dir.join(&self.name);
+        fs::create_dir_all(&dir)?;
+        let lock = self.root.join("Cargo.lock");
+        if lock.exists() && !dir.join("Cargo.lock").exists() {
+            fs::copy(lock, dir.join("Cargo.lock"))?;
+        }
+        let path = dir.join("Cargo.toml");
+        fs::write(&path, manifest.to_string())?;
+        Ok(path)
+    }
+
+    /// Builds the library with the features the host itself was built with.
+    fn build(&self, manifest: &Path) -> io::Result<bool> {
+        let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let mut command = Command::new(cargo);
+        command
+            .args(["build", "--release", "--manifest-path"])
+            .arg(manifest)
+            .arg("--target-dir")
+            .arg(&self.target_dir);
+        if cfg!(feature = "stable") {
+            command.args(["--features", "stable"]);
+        }
+        Ok(command.status()?.success())
+    }
+
+    /// The newest modification time of anything in the day's `src` directory.
+    fn last_modified(&self) -> io::Result<SystemTime> {
+        let mut newest = SystemTime::UNIX_EPOCH;
+        for entry in fs::read_dir(self.dir.join("src"))? {
+            newest = newest.max(entry?.metadata()?.modified()?);
+        }
+        Ok(newest)
+    }
+
+    /// Fingerprints the sources a kept value's type and parser can come from: the crates the day
+    /// depends on by path, and the day's own sources apart from `main.rs`.
+    fn kept_sources(&self) -> io::Result<u64> {
+        fn add_files(dir: &Path, skip: &Path, files: &mut BTreeSet<PathBuf>) -> io::Result<()> {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    add_files(&path, skip, files)?;
+                } else if path != skip {
+                    files.insert(path);
+                }
+            }
+            Ok(())
+        }
+
+        let mut crates = vec![self.dir.clone()];
+        let mut seen = BTreeSet::new();
+        let mut files = BTreeSet::new();
+        while let Some(dir) = crates.pop() {
+            let dir = dir.canonicalize()?;
+            if !seen.insert(dir.clone()) {
+                continue;
+            }
+            let manifest = fs::read_to_string(dir.join("Cargo.toml"))?;
+            files.insert(dir.join("Cargo.toml"));
+            add_files(
+                &dir.join("src"),
+                &self.dir.join("src/main.rs").canonicalize()?,
+                &mut files,
+            )?;
+            let manifest: toml::Table = manifest
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if let Some(toml::Value::Table(dependencies)) = manifest.get("dependencies") {
+                for dependency in dependencies.values() {
+                    if let Some(toml::Value::String(path)) = dependency.get("path") {
+                        crates.push(dir.join(path));
+                    }
+                }
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        for file in files {
+            file.hash(&mut hasher);
+            fs::read(&file)?.hash(&mut hasher);
+        }
+        Ok(hasher.finish())
+    }
+
+    /// Loads the freshly built library. It is copied first, since loading the same path twice
+    /// gives back the library that is already loaded.
+    fn load(&self, generation: usize) -> Result<&'static [HotPart], Box<dyn std::error::Error>> {
+        let file = |name: &str| {
+            format!(
+                "{}{}{}",
+                env::consts::DLL_PREFIX,
+                name,
+                env::consts::DLL_SUFFIX
+            )
+        };
+        let built = self.target_dir.join("release").join(file(&self.lib_name()));
+        let copy = self.target_dir.join(&self.name).join(file(&format!(
+            "{}-{}",
+            self.lib_name(),
+            generation
+        )));
+        fs::copy(&built, &copy)?;
+
+        let library = Box::leak(Box::new(unsafe { libloading::Library::new(&copy)? }));
+        let parts: libloading::Symbol<PartsFn> = unsafe { library.get(PARTS_SYMBOL)? };
+        Ok(parts())
+    }
+}
+
+/// A part's parsed input, kept across reloads.
+struct KeptInput {
+    value: Kept,
+    /// The fingerprint of the sources when the value was parsed.
+    sources: u64,
+}
+
+/// Runs every part, on the value kept from before if the newly loaded library can use it, and
+/// otherwise on one it parses now and keeps for next time.
+fn run_parts(day: &Day, parts: &[HotPart], kept: &mut Vec<Option<KeptInput>>, input: &str) {
+    let sources = match day.kept_sources() {
+        Ok(sources) => Some(sources),
+        Err(e) => {
+            eprintln!("failed to read the sources of {}: {}", day.name, e);
+            None
+        }
+    };
+    // Types defined in the library being reloaded change whenever it does.
+    let reloaded = format!("{}::", day.lib_name());
+    kept.resize_with(parts.len(), || None);
+
+    for ((number, part), slot) in (1..).zip(parts).zip(kept.iter_mut()) {
+        let keep = match (part.keep, sources) {
+            (Some(keep), Some(sources)) => Some((keep, sources)),
+            _ => None,
+        };
+        let value = match keep {
+            Some((keep, sources)) => {
+                let layout = (keep.layout)();
+                let reusable = slot.as_ref().is_some_and(|k| {
+                    k.sources == sources
+                        && *k.value.layout() == layout
+                        && !layout.type_name.contains(&reloaded)
+                });
+                if !reusable {
+                    *slot = None;
+                    let start = Instant::now();
+                    match (keep.parse)(input) {
+                        Ok(value) => {
+                            eprintln!("Part {} parsed its input ({:?})", number, start.elapsed());
+                            *slot = Some(KeptInput { value, sources });
+                        }
+                        Err(e) => {
+                            println!("Part {}: {}", number, e);
+                            continue;
+                        }
+                    }
+                }
+                slot.as_ref().map(|k| &k.value)
+            }
+            None => {
+                *slot = None;
+                None
+            }
+        };
+
+        let start = Instant::now();
+        // The value is only kept while the sources its type comes from are unchanged, and its
+        // type isn't one the reloaded library defines.
+        let outcome = unsafe { (part.run)(value, input) };
+        let elapsed = start.elapsed();
+        match outcome {
+            Ok(Outcome::Answer(a)) if a.is_multi_line() => {
+                println!("Part {} ({:?}):\n{}", number, elapsed, a)
+            }
+            Ok(Outcome::Answer(a)) => println!("Part {} ({:?}): {}", number, elapsed, a),
+            Ok(Outcome::Panicked(p)) => println!("Part {} {}", number, p),
+            Ok(Outcome::TimedOut(_)) => unreachable!(),
+            Err(e) => println!("Part {}: {}", number, e),
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip(1);
+    let day = Day::new(args.next().unwrap_or_else(|| usage()));
+    let mut input_path = day.dir.join("input.txt");
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input_path = args.next().unwrap_or_else(|| usage()).into(),
+            _ => usage(),
+        }
+    }
+
+    let input = fs::read_to_string(input_path)?;
+    let manifest = day.write_manifest()?;
+    let mut built = SystemTime::UNIX_EPOCH;
+    let mut generation = 0;
+    let mut kept = vec![];
+    loop {
+        let modified = day.last_modified()?;
+        if modified <= built {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        built = modified;
+
+        if !day.build(&manifest)? {
+            eprintln!("build failed, waiting for changes");
+            continue;
+        }
+        generation += 1;
+        match day.load(generation) {
+            Ok(parts) => run_parts(&day, parts, &mut kept, &input),
+            Err(e) => eprintln!("failed to load {}: {}", day.name, e),
+        }
+        eprintln!("waiting for changes to {}", day.dir.join("src").display());
+    }
+}
@@ -19,11 +19,14 @@ edition = "2021"
 [dependencies]
 advent = { path = "../advent" }
 combine = "*"
+
+[features]
+stable = ["advent/stable"]
 '''
 
 MAIN_RS = '''\
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 
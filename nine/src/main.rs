@@ -1,5 +1,5 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 use enum_iterator::IntoEnumIterator;
@@ -86,7 +86,7 @@ impl Floor {
 impl HasParser for Floor {
     #[into_parser]
     fn parser() -> _ {
-        let line = many1(digit().map(|d| d.to_string().parse::<u8>().unwrap()));
+        let line = many1(digit_value::<_, u8>());
         many1(line.skip(token('\n'))).map(|grid| Self { grid })
     }
 }
@@ -0,0 +1,61 @@
+//! Compares the number parsers against collecting each number into a `String` first, on inputs
+//! shaped like six's and seven's but much larger.
+
+// The benchmark harness is only on nightly.
+#![cfg(not(feature = "stable"))]
+#![feature(test)]
+
+extern crate test;
+
+use parse::prelude::*;
+use test::Bencher;
+
+fn numbers(sep: &str) -> String {
+    let numbers: Vec<String> = (0..100_000u64)
+        .map(|i| (i * 7919 % 2000).to_string())
+        .collect();
+    numbers.join(sep) + "\n"
+}
+
+fn allocating<Input>() -> impl Parser<Input, Output = u64>
+where
+    Input: combine::Stream<Token = char>,
+{
+    many1(digit()).map(|s: String| s.parse().unwrap())
+}
+
+fn parse_with<'a, P>(input: &'a str, parser: P) -> Vec<u64>
+where
+    P: Parser<easy::Stream<&'a str>, Output = Vec<u64>>,
+{
+    let mut parser = parser.skip(spaces()).skip(eof());
+    parser.easy_parse(input).unwrap().0
+}
+
+#[bench]
+fn comma_separated(b: &mut Bencher) {
+    let input = numbers(",");
+    b.bytes = input.len() as u64;
+    b.iter(|| parse_with(&input, sep_by1(u64::parser(), token(','))));
+}
+
+#[bench]
+fn comma_separated_allocating(b: &mut Bencher) {
+    let input = numbers(",");
+    b.bytes = input.len() as u64;
+    b.iter(|| parse_with(&input, sep_by1(allocating(), token(','))));
+}
+
+#[bench]
+fn newline_separated(b: &mut Bencher) {
+    let input = numbers("\n");
+    b.bytes = input.len() as u64;
+    b.iter(|| parse_with(&input, many1(u64::parser().skip(token('\n')))));
+}
+
+#[bench]
+fn newline_separated_allocating(b: &mut Bencher) {
+    let input = numbers("\n");
+    b.bytes = input.len() as u64;
+    b.iter(|| parse_with(&input, many1(allocating().skip(token('\n')))));
+}
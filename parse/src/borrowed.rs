@@ -0,0 +1,235 @@
+//! Parsers for types that borrow from the input they are parsed from, so that strings in them can
+//! be `&'a str` slices of the input rather than copies.
+
+use crate::prelude::*;
+use crate::{list_len, list_parser};
+use combine::parser::range::take_while1;
+use combine::stream::position;
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+
+pub trait HasBorrowedParser<'a>: Sized {
+    type BorrowedParser<Input: combine::RangeStream<Token = char, Range = &'a str>>: Parser<
+        Input,
+        Output = Self,
+    >;
+
+    fn borrowed_parser<Input>() -> Self::BorrowedParser<Input>
+    where
+        Input: combine::RangeStream<Token = char, Range = &'a str>;
+
+    /// What parse errors call the type, like `HasParser::name`.
+    fn name() -> String {
+        short_type_name::<Self>()
+    }
+}
+
+/// The rest of the input, like `String`.
+impl<'a> HasBorrowedParser<'a> for &'a str {
+    #[into_borrowed_parser('a, transparent)]
+    fn borrowed_parser() -> _ {
+        take_while1(|_| true)
+    }
+}
+
+// Types that can't borrow anything parse the same way as with `HasParser`, so that they can be
+// the items of borrowed lists.
+macro_rules! owned_borrowed_parser {
+    ($($id:ty),*) => {
+        $(impl<'a> HasBorrowedParser<'a> for $id {
+            #[into_borrowed_parser('a, transparent)]
+            fn borrowed_parser() -> _ {
+                <$id as HasParser>::parser()
+            }
+        })*
+    }
+}
+
+owned_borrowed_parser!(u8, u16, u32, u64, u128, usize);
+owned_borrowed_parser!(i8, i16, i32, i64, i128, isize);
+owned_borrowed_parser!(f32, f64, char, bool, String);
+
+impl<'a, T: HasBorrowedParser<'a>, Sep: Separator, Len: Length> HasBorrowedParser<'a>
+    for List<T, Sep, Len>
+{
+    #[into_borrowed_parser('a, transparent)]
+    fn borrowed_parser() -> _ {
+        Self::borrowed_parser_with_len(Len::MIN..=Len::MAX)
+    }
+
+    fn name() -> String {
+        format!("List<{}>", <T as HasBorrowedParser<'a>>::name())
+    }
+}
+
+macro_rules! borrowed_tuple_sections {
+    ($first:ident $(, $name:ident $n:literal)*) => {
+        impl<'a, $first, $($name),*> HasBorrowedParser<'a> for Sections<($first, $($name),*)>
+        where
+            $first: HasBorrowedParser<'a>,
+            $($name: HasBorrowedParser<'a>,)*
+        {
+            #[into_borrowed_parser('a, transparent)]
+            fn borrowed_parser() -> _ {
+                (
+                    with_context($first::borrowed_parser(), "in section 1"),
+                    $(with_context(
+                        BlankLine::parser().with($name::borrowed_parser()),
+                        concat!("in section ", $n),
+                    ),)*
+                )
+                    .map(Self)
+            }
+        }
+    }
+}
+
+borrowed_tuple_sections!(A, B 2);
+borrowed_tuple_sections!(A, B 2, C 3);
+borrowed_tuple_sections!(A, B 2, C 3, D 4);
+borrowed_tuple_sections!(A, B 2, C 3, D 4, E 5);
+
+impl<'a, T: HasBorrowedParser<'a>, Sep: Separator, Len: Length> List<T, Sep, Len> {
+    /// Like `parser_with_len`, for borrowed items.
+    pub fn borrowed_parser_with_len<Input>(
+        len: impl RangeBounds<usize>,
+    ) -> impl Parser<Input, Output = Self> + Captures<'a>
+    where
+        Input: combine::RangeStream<Token = char, Range = &'a str>,
+    {
+        list_parser(
+            list_len(len),
+            Sep::TRAILING,
+            <Self as HasBorrowedParser<'a>>::name,
+            T::borrowed_parser,
+            Sep::parser,
+        )
+        .map(|v: Vec<_>| v.into())
+    }
+}
+
+/// Like `recursive`, for types that borrow from the input.
+pub fn recursive_borrowed<'a, Input, T>() -> RecursiveBorrowed<Input, T>
+where
+    Input: combine::RangeStream<Token = char, Range = &'a str>,
+    T: HasBorrowedParser<'a>,
+{
+    RecursiveBorrowed(PhantomData)
+}
+
+pub struct RecursiveBorrowed<Input, T>(PhantomData<fn(Input) -> T>);
+
+impl<'a, Input, T> Parser<Input> for RecursiveBorrowed<Input, T>
+where
+    Input: combine::RangeStream<Token = char, Range = &'a str>,
+    T: HasBorrowedParser<'a>,
+{
+    type Output = T;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<T, Input::Error> {
+        T::borrowed_parser::<Input>().parse_lazy(input)
+    }
+
+    fn add_error(&mut self, errors: &mut combine::error::Tracked<Input::Error>) {
+        T::borrowed_parser::<Input>().add_error(errors)
+    }
+}
+
+/// Lets an `impl Trait` return type borrow from the input without the input having to outlive it.
+pub trait Captures<'a> {}
+
+impl<'a, T: ?Sized> Captures<'a> for T {}
+
+/// Like `parse_str`, for a value that borrows from `input`.
+pub fn parse_borrowed<'a, T: HasBorrowedParser<'a>>(input: &'a str) -> Result<T> {
+    let (t, _): (T, _) = T::borrowed_parser()
+        .skip(spaces())
+        .skip(eof())
+        .easy_parse(position::Stream::new(input))
+        .map_err(|e| SyntaxError::new(input, e))?;
+    Ok(t)
+}
+
+#[test]
+fn borrowed() {
+    #[derive(Debug, PartialEq)]
+    struct Pair<'a> {
+        key: &'a str,
+        value: u32,
+    }
+
+    impl<'input> HasBorrowedParser<'input> for Pair<'input> {
+        #[into_borrowed_parser('input)]
+        fn borrowed_parser() -> _ {
+            let key = take_while1(|c: char| c.is_alphabetic());
+            (key.skip(token('=')), u32::parser()).map(|(key, value)| Self { key, value })
+        }
+    }
+
+    let input = String::from("a=1,bc=2\n");
+    let pairs: List<Pair<'_>, Comma> = parse_borrowed(&input).unwrap();
+    assert_eq!(
+        &pairs[..],
+        [
+            Pair { key: "a", value: 1 },
+            Pair {
+                key: "bc",
+                value: 2
+            }
+        ]
+    );
+    assert!(std::ptr::eq(pairs[1].key.as_ptr(), &input.as_bytes()[4]));
+
+    let s: &str = parse_borrowed("a b").unwrap();
+    assert_eq!(s, "a b");
+
+    let e = match parse_borrowed::<List<Pair<'_>, Comma>>("a=1,=2") {
+        Err(Error::Syntax(e)) => e,
+        r => panic!("{:?}", r.map(|l| l.len())),
+    };
+    assert_eq!(e.context, ["in item 2 of List<Pair>", "in Pair"]);
+
+    let Sections((first, rest)): Sections<(Pair<'_>, &str)> = parse_borrowed("a=1\n\nb c").unwrap();
+    assert_eq!((first.key, rest), ("a", "b c"));
+    let e = match parse_borrowed::<Sections<(Pair<'_>, Pair<'_>)>>("a=1\n\nb") {
+        Err(Error::Syntax(e)) => e,
+        r => panic!("{:?}", r.map(|s| s.0)),
+    };
+    assert_eq!(e.context, ["in section 2", "in Pair"]);
+}
+
+#[test]
+fn recursive_borrowed_types() {
+    #[derive(Debug, PartialEq)]
+    enum Tree<'a> {
+        Leaf(&'a str),
+        Node(Vec<Tree<'a>>),
+    }
+
+    impl<'a> HasBorrowedParser<'a> for Tree<'a> {
+        #[into_borrowed_parser('a)]
+        fn borrowed_parser() -> _ {
+            let node = between(
+                token('['),
+                token(']'),
+                sep_by(recursive_borrowed::<_, Tree<'a>>(), token(',')),
+            );
+            let leaf = take_while1(|c: char| c.is_alphabetic());
+            leaf.map(Self::Leaf).or(node.map(Self::Node))
+        }
+    }
+
+    use Tree::*;
+    assert_eq!(
+        parse_borrowed::<Tree<'_>>("[a,[bc]]").unwrap(),
+        Node(vec![Leaf("a"), Node(vec![Leaf("bc")])])
+    );
+
+    let e = match parse_borrowed::<Tree<'_>>("[a,[1]]") {
+        Err(Error::Syntax(e)) => e,
+        r => panic!("{:?}", r),
+    };
+    assert_eq!(e.column, 5);
+    assert_eq!(e.context, ["in Tree", "in Tree", "in Tree"]);
+}
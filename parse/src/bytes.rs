@@ -0,0 +1,300 @@
+//! Parsers over bytes rather than characters, for input that is always ASCII or isn't text at
+//! all. They mirror the `char` parsers of the same types.
+
+use crate::prelude::*;
+use crate::{list_len, list_parser};
+use combine::parser::byte;
+use combine::parser::combinator::recognize;
+use combine::parser::token::tokens_cmp;
+use combine::stream::{position, StreamErrorFor};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+
+pub trait HasByteParser {
+    type ByteParser<Input: combine::Stream<Token = u8>>: Parser<Input, Output = Self>;
+
+    fn byte_parser<Input>() -> Self::ByteParser<Input>
+    where
+        Input: combine::Stream<Token = u8>;
+
+    /// What parse errors call the type, like `HasParser::name`.
+    fn name() -> String {
+        short_type_name::<Self>()
+    }
+}
+
+/// Matches the bytes of `s`, like `string` does its characters.
+pub fn byte_string<Input>(s: &'static str) -> impl Parser<Input, Output = &'static str>
+where
+    Input: combine::Stream<Token = u8>,
+{
+    tokens_cmp(s.bytes(), |l: u8, r: u8| l == r)
+        .expected(s)
+        .map(move |_| s)
+}
+
+impl<T: Integer> Extend<u8> for Digits<T> {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        for b in iter {
+            self.0 = self.0.and_then(|n| n.push_digit(b - b'0'));
+        }
+    }
+}
+
+macro_rules! number_byte_parser {
+    ($($id:ty),*) => {
+        $(impl HasByteParser for $id {
+            #[into_byte_parser(transparent)]
+            fn byte_parser() -> _ {
+                many1(byte::digit()).and_then(|digits: Digits<Self>| {
+                    digits.finish(false).ok_or_else(|| {
+                        StreamErrorFor::<Input>::message_static_message(
+                            "number too large to fit in target type",
+                        )
+                    })
+                })
+            }
+        })*
+    }
+}
+
+number_byte_parser!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! signed_number_byte_parser {
+    ($($id:ty),*) => {
+        $(impl HasByteParser for $id {
+            #[into_byte_parser(transparent)]
+            fn byte_parser() -> _ {
+                let sign = optional(one_of(b"+-".iter().copied()));
+                (sign, many1(byte::digit())).and_then(|(sign, digits): (_, Digits<Self>)| {
+                    digits.finish(sign == Some(b'-')).ok_or_else(|| {
+                        StreamErrorFor::<Input>::message_static_message(
+                            "number too large to fit in target type",
+                        )
+                    })
+                })
+            }
+        })*
+    }
+}
+
+signed_number_byte_parser!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! float_byte_parser {
+    ($($id:ty),*) => {
+        $(impl HasByteParser for $id {
+            #[into_byte_parser(transparent)]
+            fn byte_parser() -> _ {
+                let sign = || optional(one_of(b"+-".iter().copied()));
+                let fraction = optional((byte::byte(b'.'), skip_many(byte::digit())));
+                let exponent = optional(attempt((
+                    one_of(b"eE".iter().copied()),
+                    sign(),
+                    skip_many1(byte::digit()),
+                )));
+                recognize((sign(), skip_many1(byte::digit()), fraction, exponent)).and_then(
+                    |b: Vec<u8>| {
+                        // Only ASCII was matched.
+                        std::str::from_utf8(&b)
+                            .unwrap()
+                            .parse()
+                            .map_err(StreamErrorFor::<Input>::message_format)
+                    },
+                )
+            }
+        })*
+    }
+}
+
+float_byte_parser!(f32, f64);
+
+/// Parses digits matched by `digit` in the given radix.
+fn radix_byte_parser<Input, T, D>(digit: D, radix: u32) -> impl Parser<Input, Output = T>
+where
+    Input: combine::Stream<Token = u8>,
+    T: FromStrRadix,
+    D: Parser<Input, Output = u8>,
+{
+    many1(digit).and_then(move |b: Vec<u8>| {
+        T::from_str_radix(std::str::from_utf8(&b).unwrap(), radix)
+            .map_err(StreamErrorFor::<Input>::message_format)
+    })
+}
+
+impl<T: FromStrRadix> HasByteParser for Hex<T> {
+    #[into_byte_parser(transparent)]
+    fn byte_parser() -> _ {
+        radix_byte_parser(byte::hex_digit(), 16).map(Self)
+    }
+}
+
+impl<T: FromStrRadix> HasByteParser for Bin<T> {
+    #[into_byte_parser(transparent)]
+    fn byte_parser() -> _ {
+        radix_byte_parser(one_of(b"01".iter().copied()), 2).map(Self)
+    }
+}
+
+impl HasByteParser for NewLine {
+    #[into_byte_parser(transparent)]
+    fn byte_parser() -> _ {
+        let line_end = attempt((token(b'\n'), not_followed_by(token(b'\n'))));
+        let before_blank_line = look_ahead((token(b'\n'), token(b'\n')));
+        line_end.map(|_| Self).or(before_blank_line.map(|_| Self))
+    }
+}
+
+impl HasByteParser for BlankLine {
+    #[into_byte_parser(transparent)]
+    fn byte_parser() -> _ {
+        (token(b'\n'), token(b'\n'), look_ahead(any())).map(|_| Self)
+    }
+}
+
+impl HasByteParser for Whitespace {
+    #[into_byte_parser(transparent)]
+    fn byte_parser() -> _ {
+        skip_many1(one_of(b" \t".iter().copied())).map(|_| Self)
+    }
+}
+
+impl<Sep: Separator + HasByteParser> HasByteParser for Trailing<Sep> {
+    #[into_byte_parser(transparent)]
+    fn byte_parser() -> _ {
+        Sep::byte_parser().map(|_| Self(PhantomData))
+    }
+}
+
+impl<T: HasByteParser, Sep: Separator + HasByteParser, Len: Length> HasByteParser
+    for List<T, Sep, Len>
+{
+    #[into_byte_parser(transparent)]
+    fn byte_parser() -> _ {
+        Self::byte_parser_with_len(Len::MIN..=Len::MAX)
+    }
+
+    fn name() -> String {
+        format!("List<{}>", <T as HasByteParser>::name())
+    }
+}
+
+impl<T: HasByteParser, Sep: Separator + HasByteParser, Len: Length> List<T, Sep, Len> {
+    /// Like `parser_with_len`, for bytes.
+    pub fn byte_parser_with_len<Input>(
+        len: impl RangeBounds<usize>,
+    ) -> impl Parser<Input, Output = Self>
+    where
+        Input: combine::Stream<Token = u8>,
+    {
+        list_parser(
+            list_len(len),
+            Sep::TRAILING,
+            <Self as HasByteParser>::name,
+            T::byte_parser,
+            Sep::byte_parser,
+        )
+        .map(|v: Vec<_>| v.into())
+    }
+}
+
+/// Like `recursive`, for types that parse bytes.
+pub fn recursive_bytes<Input, T>() -> RecursiveBytes<Input, T>
+where
+    Input: combine::Stream<Token = u8>,
+    T: HasByteParser,
+{
+    RecursiveBytes(PhantomData)
+}
+
+pub struct RecursiveBytes<Input, T>(PhantomData<fn(Input) -> T>);
+
+impl<Input, T> Parser<Input> for RecursiveBytes<Input, T>
+where
+    Input: combine::Stream<Token = u8>,
+    T: HasByteParser,
+{
+    type Output = T;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<T, Input::Error> {
+        T::byte_parser::<Input>().parse_lazy(input)
+    }
+
+    fn add_error(&mut self, errors: &mut combine::error::Tracked<Input::Error>) {
+        T::byte_parser::<Input>().add_error(errors)
+    }
+}
+
+pub fn parse_bytes<T: HasByteParser>(input: &[u8]) -> Result<T> {
+    let (t, _): (T, _) = T::byte_parser()
+        .skip(byte::spaces())
+        .skip(eof())
+        .easy_parse(position::Stream::with_positioner(
+            input,
+            position::SourcePosition::default(),
+        ))
+        .map_err(|e| SyntaxError::from_bytes(input, e))?;
+    Ok(t)
+}
+
+#[test]
+fn bytes() {
+    assert_eq!(parse_bytes::<u8>(b"255").unwrap(), 255);
+    assert_eq!(parse_bytes::<i32>(b"-12").unwrap(), -12);
+    assert_eq!(parse_bytes::<f64>(b"1.5e3").unwrap(), 1500.0);
+    assert_eq!(parse_bytes::<Hex<u16>>(b"fF").unwrap(), Hex(255));
+
+    let list: List<List<u32, Comma>, NewLine> = parse_bytes(b"1,2\n3\n").unwrap();
+    let rows: Vec<Vec<u32>> = list.iter().map(|l| l.to_vec()).collect();
+    assert_eq!(rows, [vec![1, 2], vec![3]]);
+    let words: List<u8, Whitespace, Exactly<3>> = parse_bytes(b"1  2\t3").unwrap();
+    assert_eq!(&words[..], [1, 2, 3]);
+    let blocks: List<List<u8, NewLine>, BlankLine> = parse_bytes(b"1\n2\n\n3\n").unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert!(parse_bytes::<List<u8, BlankLine>>(b"1\n2").is_err());
+
+    let e = match parse_bytes::<List<u32, Comma>>(b"1,x") {
+        Err(Error::Syntax(e)) => e,
+        r => panic!("{:?}", r.map(|l| l.to_vec())),
+    };
+    assert_eq!((e.line, e.column), (1, 3));
+    assert_eq!(e.unexpected, [Token::Char('x')]);
+    assert_eq!(e.context, ["in item 2 of List<u32>"]);
+    assert_eq!(e.source_line, "1,x");
+}
+
+#[test]
+fn recursive_byte_types() {
+    #[derive(Debug, PartialEq)]
+    enum Expr {
+        Number(u32),
+        List(Vec<Expr>),
+    }
+
+    impl HasByteParser for Expr {
+        #[into_byte_parser]
+        fn byte_parser() -> _ {
+            let list = between(
+                byte::byte(b'['),
+                byte::byte(b']'),
+                sep_by(recursive_bytes::<_, Expr>(), byte::byte(b',')),
+            );
+            u32::byte_parser()
+                .map(Self::Number)
+                .or(list.map(Self::List))
+        }
+    }
+
+    use Expr::*;
+    assert_eq!(
+        parse_bytes::<Expr>(b"[1,[2]]").unwrap(),
+        List(vec![Number(1), List(vec![Number(2)])])
+    );
+
+    let e = match parse_bytes::<Expr>(b"[1,[x]]") {
+        Err(Error::Syntax(e)) => e,
+        r => panic!("{:?}", r),
+    };
+    assert_eq!(e.column, 5);
+    assert_eq!(e.context, ["in Expr", "in Expr", "in Expr"]);
+}
@@ -0,0 +1,321 @@
+use combine::easy;
+use combine::error::{ParseError as _, ParseResult, StreamError as _, Tracked};
+use combine::stream::position::SourcePosition;
+use combine::stream::{StreamErrorFor, StreamOnce};
+use combine::{Parser, Stream};
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::fmt;
+use std::rc::Rc;
+use std::{io, num};
+
+#[derive(Debug)]
+pub enum Error {
+    ParseInt(num::ParseIntError),
+    Io(io::Error),
+    ParseError(String),
+    Syntax(Box<SyntaxError>),
+    /// Every line that failed, from `parse_lines`.
+    Many(Vec<SyntaxError>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParseInt(e) => write!(f, "{}", e),
+            Self::Io(e) => write!(f, "{}", e),
+            Self::ParseError(e) => write!(f, "{}", e),
+            Self::Syntax(e) => write!(f, "{}", e),
+            Self::Many(errors) => {
+                write!(f, "{} lines failed to parse", errors.len())?;
+                for e in errors {
+                    write!(f, "\n\n{}", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ParseInt(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::ParseError(_) | Self::Syntax(_) | Self::Many(_) => None,
+        }
+    }
+}
+
+impl From<Infallible> for Error {
+    fn from(_: Infallible) -> Self {
+        unreachable!()
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<num::ParseIntError> for Error {
+    fn from(e: num::ParseIntError) -> Self {
+        Self::ParseInt(e)
+    }
+}
+
+impl From<SyntaxError> for Error {
+    fn from(e: SyntaxError) -> Self {
+        Self::Syntax(Box::new(e))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Something a parser expected or didn't expect to find.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Char(char),
+    /// A piece of the input.
+    Range(String),
+    /// A description like "digit" or "end of input".
+    Description(String),
+}
+
+impl Token {
+    fn from_info(info: easy::Info<char, &str>) -> Self {
+        match info {
+            easy::Info::Token(c) => Self::Char(c),
+            easy::Info::Range(r) => Self::Range(r.into()),
+            easy::Info::Static(s) => Self::Description(s.into()),
+            easy::Info::Owned(s) => Self::Description(s),
+        }
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(c) => write!(f, "`{}`", c.escape_debug()),
+            Self::Range(r) => write!(f, "`{}`", r.escape_debug()),
+            Self::Description(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+/// What was being parsed when an error happened, like `in item 3 of List<Line>`. It is added to
+/// errors as an error of its own, so that it is kept apart from the messages parsers give.
+#[derive(Debug)]
+struct Context(String);
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Context {}
+
+/// Adds `context` to the errors of `parser`, the way `Parser::message` adds a message. The
+/// context is only formatted when there is an error.
+pub fn with_context<Input, P, C>(parser: P, context: C) -> WithContext<P, C>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    C: fmt::Display,
+{
+    WithContext(parser, context)
+}
+
+pub struct WithContext<P, C>(P, C);
+
+impl<P, C: fmt::Display> WithContext<P, C> {
+    fn add_context<Input: Stream>(&self, errors: &mut Input::Error) {
+        errors.add(StreamErrorFor::<Input>::other(Context(self.1.to_string())));
+    }
+}
+
+impl<Input, P, C> Parser<Input> for WithContext<P, C>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    C: fmt::Display,
+{
+    type Output = P::Output;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<P::Output, Input::Error> {
+        match self.0.parse_lazy(input) {
+            // Errors that consumed nothing get their context in `add_error`.
+            ParseResult::CommitErr(mut errors) => {
+                self.add_context::<Input>(&mut errors);
+                ParseResult::CommitErr(errors)
+            }
+            result => result,
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors);
+        self.add_context::<Input>(&mut errors.error);
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_committed_expected_error(errors)
+    }
+
+    fn parser_count(&self) -> combine::ErrorOffset {
+        self.0.parser_count()
+    }
+}
+
+/// Context added by `#[into_parser]` to the errors of a type's parser, given the function that
+/// names the type. It is only formatted when an error is reported.
+#[derive(Clone, Copy)]
+pub struct TypeContext(fn() -> String);
+
+impl TypeContext {
+    pub fn new(name: fn() -> String) -> Self {
+        Self(name)
+    }
+}
+
+impl fmt::Display for TypeContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "in {}", (self.0)())
+    }
+}
+
+/// Context added to the errors of a list's items, saying which item failed. The count is
+/// shared with the parser that counts the items.
+pub(crate) struct ItemContext {
+    count: Rc<Cell<usize>>,
+    list: fn() -> String,
+}
+
+impl ItemContext {
+    pub(crate) fn new(count: Rc<Cell<usize>>, list: fn() -> String) -> Self {
+        Self { count, list }
+    }
+}
+
+impl fmt::Display for ItemContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "in item {} of {}", self.count.get(), (self.list)())
+    }
+}
+
+/// Where and why some input failed to parse, along with the line it failed on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// 1-based, like `column`.
+    pub line: usize,
+    pub column: usize,
+    pub unexpected: Vec<Token>,
+    pub expected: Vec<Token>,
+    pub messages: Vec<String>,
+    /// What was being parsed, outermost first, like `in item 3 of List<Line>`.
+    pub context: Vec<String>,
+    pub source_line: String,
+}
+
+impl SyntaxError {
+    /// Like `new`, for errors from parsing bytes. The input is taken to be text, since that is
+    /// the only way to show it.
+    pub fn from_bytes(input: &[u8], errors: easy::Errors<u8, &[u8], SourcePosition>) -> Self {
+        let info = |info| match info {
+            easy::Info::Token(b) => easy::Info::Token(b as char),
+            easy::Info::Range(r) => easy::Info::Owned(String::from_utf8_lossy(r).into_owned()),
+            easy::Info::Static(s) => easy::Info::Static(s),
+            easy::Info::Owned(s) => easy::Info::Owned(s),
+        };
+        let errors = easy::Errors {
+            position: errors.position,
+            errors: errors
+                .errors
+                .into_iter()
+                .map(|e| match e {
+                    easy::Error::Unexpected(i) => easy::Error::Unexpected(info(i)),
+                    easy::Error::Expected(i) => easy::Error::Expected(info(i)),
+                    easy::Error::Message(i) => easy::Error::Message(info(i)),
+                    easy::Error::Other(o) => easy::Error::Other(o),
+                })
+                .collect(),
+        };
+        Self::new(&String::from_utf8_lossy(input), errors)
+    }
+
+    pub fn new(input: &str, errors: easy::Errors<char, &str, SourcePosition>) -> Self {
+        let line = errors.position.line as usize;
+        let mut e = Self {
+            line,
+            column: errors.position.column as usize,
+            unexpected: vec![],
+            expected: vec![],
+            messages: vec![],
+            context: vec![],
+            source_line: input.lines().nth(line - 1).unwrap_or("").into(),
+        };
+        for error in errors.errors {
+            match error {
+                easy::Error::Unexpected(i) => e.unexpected.push(Token::from_info(i)),
+                easy::Error::Expected(i) => e.expected.push(Token::from_info(i)),
+                easy::Error::Message(i) => e.messages.push(Token::from_info(i).to_string()),
+                easy::Error::Other(o) => match o.downcast_ref::<Context>() {
+                    Some(c) => e.context.push(c.0.clone()),
+                    None => e.messages.push(o.to_string()),
+                },
+            }
+        }
+        // Context is added as errors bubble up, so the innermost comes first.
+        e.context.reverse();
+        e
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, tokens: &[Token]) -> fmt::Result {
+    for (i, t) in tokens.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", if i + 1 == tokens.len() { " or " } else { ", " })?;
+        }
+        write!(f, "{}", t)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        for c in &self.context {
+            write!(f, ", {}", c)?;
+        }
+        let mut separator = ": ";
+        if !self.unexpected.is_empty() {
+            write!(f, "{}unexpected ", separator)?;
+            write_list(f, &self.unexpected)?;
+            separator = ", ";
+        }
+        if !self.expected.is_empty() {
+            write!(f, "{}expected ", separator)?;
+            write_list(f, &self.expected)?;
+        }
+        writeln!(f)?;
+
+        // Tabs are kept under the caret so that it lines up however wide they are shown.
+        let number = self.line.to_string();
+        let indent: String = self
+            .source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        writeln!(f, "{} |", " ".repeat(number.len()))?;
+        writeln!(f, "{} | {}", number, self.source_line)?;
+        write!(f, "{} | {}^", " ".repeat(number.len()), indent)?;
+        for m in &self.messages {
+            write!(f, "\n{}", m)?;
+        }
+        Ok(())
+    }
+}
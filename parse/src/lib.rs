@@ -1,24 +1,38 @@
-#![feature(generic_associated_types)]
-#![feature(type_alias_impl_trait)]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
 
 use combine::eof;
+use combine::error::StreamError as _;
 use combine::parser::char::spaces;
-use combine::stream::{easy, position};
+use combine::parser::combinator::recognize;
+use combine::stream::{position, StreamErrorFor};
 use prelude::*;
-use std::convert::Infallible;
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::Hash;
 use std::marker::PhantomData;
+use std::rc::Rc;
 use std::{
-    io, iter, num,
-    ops::{Deref, DerefMut},
+    iter, num,
+    ops::{Bound, Deref, DerefMut, RangeBounds},
     slice, str, vec,
 };
 
+mod borrowed;
+mod bytes;
+mod error;
+
+pub use borrowed::{parse_borrowed, recursive_borrowed, HasBorrowedParser, RecursiveBorrowed};
+pub use bytes::{byte_string, parse_bytes, recursive_bytes, HasByteParser, RecursiveBytes};
+use error::ItemContext;
+pub use error::{with_context, Error, Result, SyntaxError, Token, TypeContext, WithContext};
+
 pub mod prelude {
     pub use super::*;
     pub use combine::parser::char::*;
     pub use combine::*;
     pub use combine::{Parser, Stream};
-    pub use parse_macro::into_parser;
+    pub use parse_macro::{into_borrowed_parser, into_byte_parser, into_parser};
     pub use std::str::FromStr;
 }
 
@@ -28,47 +42,206 @@ pub trait HasParser {
     fn parser<Input>() -> Self::Parser<Input>
     where
         Input: combine::Stream<Token = char>;
+
+    /// What parse errors call the type, by default its name without module paths. Override it to
+    /// give the type a more helpful name.
+    fn name() -> String {
+        short_type_name::<Self>()
+    }
 }
 
-#[derive(Debug)]
-pub enum Error {
-    ParseInt(num::ParseIntError),
-    Io(io::Error),
-    ParseError(String),
+/// The parser `#[into_parser]` gives a type with the `stable` feature, since stable Rust can't
+/// name the type of the parser the body returns. That parser is built again each time it parses,
+/// instead of once.
+#[cfg(feature = "stable")]
+pub struct StableParser<Input: combine::Stream, T> {
+    parse: fn(&mut Input) -> ParseResult<T, Input::Error>,
+    add_error: fn(&mut combine::error::Tracked<Input::Error>),
 }
 
-impl From<Infallible> for Error {
-    fn from(_: Infallible) -> Self {
-        unreachable!()
+#[cfg(feature = "stable")]
+impl<Input: combine::Stream, T> StableParser<Input, T> {
+    #[doc(hidden)]
+    pub fn new(
+        parse: fn(&mut Input) -> ParseResult<T, Input::Error>,
+        add_error: fn(&mut combine::error::Tracked<Input::Error>),
+    ) -> Self {
+        Self { parse, add_error }
     }
 }
 
-impl From<io::Error> for Error {
-    fn from(e: io::Error) -> Self {
-        Self::Io(e)
+#[cfg(feature = "stable")]
+impl<Input: combine::Stream, T> Parser<Input> for StableParser<Input, T> {
+    type Output = T;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<T, Input::Error> {
+        (self.parse)(input)
     }
+
+    fn add_error(&mut self, errors: &mut combine::error::Tracked<Input::Error>) {
+        (self.add_error)(errors)
+    }
+}
+
+// The stream and output types are given explicitly to these, since nothing else would say what
+// the parser built by `#[into_parser]` parses.
+
+#[cfg(feature = "stable")]
+#[doc(hidden)]
+pub fn run_stable_parser<Input, T, P>(
+    input: &mut Input,
+    mut parser: P,
+) -> ParseResult<T, Input::Error>
+where
+    Input: combine::Stream,
+    P: Parser<Input, Output = T>,
+{
+    parser.parse_lazy(input)
+}
+
+#[cfg(feature = "stable")]
+#[doc(hidden)]
+pub fn add_stable_parser_error<Input, T, P>(
+    errors: &mut combine::error::Tracked<Input::Error>,
+    mut parser: P,
+) where
+    Input: combine::Stream,
+    P: Parser<Input, Output = T>,
+{
+    parser.add_error(errors)
 }
 
-impl From<num::ParseIntError> for Error {
-    fn from(e: num::ParseIntError) -> Self {
-        Self::ParseInt(e)
+/// The name of a type with the module paths left out, like `List<Line, NewLine>`.
+pub fn short_type_name<T: ?Sized>() -> String {
+    let mut name = String::new();
+    let mut path = String::new();
+    for c in std::any::type_name::<T>().chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            path.push(c);
+        } else {
+            name += path.rsplit("::").next().unwrap();
+            path.clear();
+            name.push(c);
+        }
+    }
+    name += path.rsplit("::").next().unwrap();
+
+    // Lifetimes, like the `'_` of `Cave<'_>`, say nothing about what was parsed.
+    let mut short = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\'' {
+            short.push(c);
+            continue;
+        }
+        while chars
+            .next_if(|&c| c.is_alphanumeric() || c == '_')
+            .is_some()
+        {}
+        chars.next_if_eq(&',');
+        chars.next_if_eq(&' ');
     }
+    short.replace("<>", "")
+}
+
+/// Integer types that decimal digits can be accumulated into directly.
+pub trait Integer: Copy {
+    const ZERO: Self;
+
+    /// Appends a digit to the number, or returns `None` on overflow. Signed types accumulate
+    /// towards negative numbers so that their minimum value can be parsed.
+    fn push_digit(self, digit: u8) -> Option<Self>;
+
+    /// Turns the accumulated digits into the number, or returns `None` on overflow.
+    fn finish(self, negative: bool) -> Option<Self>;
 }
 
-impl From<easy::Errors<char, &str, position::SourcePosition>> for Error {
-    fn from(e: easy::Errors<char, &str, position::SourcePosition>) -> Self {
-        Self::ParseError(e.to_string())
+macro_rules! unsigned_integer {
+    ($($id:ty),*) => {
+        $(impl Integer for $id {
+            const ZERO: Self = 0;
+
+            fn push_digit(self, digit: u8) -> Option<Self> {
+                self.checked_mul(10)?.checked_add(digit as Self)
+            }
+
+            fn finish(self, negative: bool) -> Option<Self> {
+                (!negative || self == 0).then(|| self)
+            }
+        })*
     }
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+unsigned_integer!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! signed_integer {
+    ($($id:ty),*) => {
+        $(impl Integer for $id {
+            const ZERO: Self = 0;
+
+            fn push_digit(self, digit: u8) -> Option<Self> {
+                self.checked_mul(10)?.checked_sub(digit as Self)
+            }
+
+            fn finish(self, negative: bool) -> Option<Self> {
+                if negative {
+                    Some(self)
+                } else {
+                    self.checked_neg()
+                }
+            }
+        })*
+    }
+}
+
+signed_integer!(i8, i16, i32, i64, i128, isize);
+
+/// Collects digits straight into an `Integer`, so that parsing a number doesn't allocate. Holds
+/// `None` once the number has overflowed.
+pub struct Digits<T>(Option<T>);
+
+impl<T: Integer> Default for Digits<T> {
+    fn default() -> Self {
+        Self(Some(T::ZERO))
+    }
+}
+
+impl<T: Integer> Extend<char> for Digits<T> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for c in iter {
+            self.0 = self.0.and_then(|n| n.push_digit(c as u8 - b'0'));
+        }
+    }
+}
+
+impl<T: Integer> Digits<T> {
+    pub fn finish(self, negative: bool) -> Option<T> {
+        self.0?.finish(negative)
+    }
+}
+
+/// Parses a single decimal digit as its value.
+pub fn digit_value<Input, T>() -> impl Parser<Input, Output = T>
+where
+    Input: combine::Stream<Token = char>,
+    T: From<u8>,
+{
+    digit().map(|c: char| T::from(c as u8 - b'0'))
+}
 
 macro_rules! number_parser {
     ($($id:ty),*) => {
         $(impl HasParser for $id {
-            #[into_parser]
+            #[into_parser(transparent)]
             fn parser() -> _ {
-                many1(digit()).map(|s: String| s.parse::<Self>().unwrap())
+                many1(digit()).and_then(|digits: Digits<Self>| {
+                    digits.finish(false).ok_or_else(|| {
+                        StreamErrorFor::<Input>::message_static_message(
+                            "number too large to fit in target type",
+                        )
+                    })
+                })
             }
         })*
     }
@@ -76,53 +249,483 @@ macro_rules! number_parser {
 
 number_parser!(u8, u16, u32, u64, u128, usize);
 
+macro_rules! signed_number_parser {
+    ($($id:ty),*) => {
+        $(impl HasParser for $id {
+            #[into_parser(transparent)]
+            fn parser() -> _ {
+                let sign = optional(one_of("+-".chars()));
+                (sign, many1(digit())).and_then(|(sign, digits): (_, Digits<Self>)| {
+                    digits.finish(sign == Some('-')).ok_or_else(|| {
+                        StreamErrorFor::<Input>::message_static_message(
+                            "number too large to fit in target type",
+                        )
+                    })
+                })
+            }
+        })*
+    }
+}
+
+signed_number_parser!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! float_parser {
+    ($($id:ty),*) => {
+        $(impl HasParser for $id {
+            #[into_parser(transparent)]
+            fn parser() -> _ {
+                let sign = || optional(one_of("+-".chars()));
+                let fraction = optional((token('.'), skip_many(digit())));
+                let exponent = optional(attempt((one_of("eE".chars()), sign(), skip_many1(digit()))));
+                from_str(recognize::<String, _, _>((sign(), skip_many1(digit()), fraction, exponent)))
+            }
+        })*
+    }
+}
+
+float_parser!(f32, f64);
+
+/// Integer types that can be parsed from digits in another radix, for `Hex` and `Bin`.
+pub trait FromStrRadix: Sized {
+    fn from_str_radix(s: &str, radix: u32) -> std::result::Result<Self, num::ParseIntError>;
+}
+
+macro_rules! from_str_radix {
+    ($($id:ty),*) => {
+        $(impl FromStrRadix for $id {
+            fn from_str_radix(s: &str, radix: u32) -> std::result::Result<Self, num::ParseIntError> {
+                <$id>::from_str_radix(s, radix)
+            }
+        })*
+    }
+}
+
+from_str_radix!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// An integer written in hexadecimal digits, without any prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hex<T>(pub T);
+
+impl<T: FromStrRadix> HasParser for Hex<T> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        many1(hex_digit()).and_then(|s: String| {
+            T::from_str_radix(&s, 16)
+                .map(Self)
+                .map_err(StreamErrorFor::<Input>::message_format)
+        })
+    }
+}
+
+/// An integer written in binary digits, without any prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bin<T>(pub T);
+
+impl<T: FromStrRadix> HasParser for Bin<T> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        many1(one_of("01".chars())).and_then(|s: String| {
+            T::from_str_radix(&s, 2)
+                .map(Self)
+                .map_err(StreamErrorFor::<Input>::message_format)
+        })
+    }
+}
+
 impl HasParser for String {
-    #[into_parser]
+    #[into_parser(transparent)]
     fn parser() -> _ {
         many1(any())
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct Comma;
+/// Any character other than a newline, so that a line of characters stops at its end.
+impl HasParser for char {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        satisfy(|c| c != '\n')
+    }
+}
+
+impl HasParser for bool {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        string("true")
+            .map(|_| true)
+            .or(string("false").map(|_| false))
+    }
+}
+
+impl<T: HasParser> HasParser for Option<T> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        optional(T::parser())
+    }
+}
+
+impl<T: HasParser> HasParser for Box<T> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        T::parser().map(Box::new)
+    }
+}
+
+// Collections parse their items back to back, like the characters of a line. Items with a
+// separator between them are a `List`.
+
+impl<T: HasParser> HasParser for Vec<T> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        many1(T::parser())
+    }
+}
+
+impl<T: HasParser, const N: usize> HasParser for [T; N] {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        count_min_max(N, N, T::parser()).map(|v: Vec<T>| match v.try_into() {
+            Ok(array) => array,
+            Err(_) => unreachable!(),
+        })
+    }
+}
+
+impl<T: HasParser + Eq + Hash> HasParser for HashSet<T> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        many1(T::parser())
+    }
+}
+
+impl<T: HasParser + Ord> HasParser for BTreeSet<T> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        many1(T::parser())
+    }
+}
+
+/// A tuple whose items are separated by `Sep`, like the `x,y` of `Tuple<(u32, u32), Comma>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tuple<T, Sep>(T, PhantomData<Sep>);
+
+impl<T, Sep> Tuple<T, Sep> {
+    pub fn new(t: T) -> Self {
+        Self(t, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, Sep> Deref for Tuple<T, Sep> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, Sep> DerefMut for Tuple<T, Sep> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+macro_rules! tuple_parser {
+    ($first:ident $(, $name:ident)*) => {
+        impl<$first: HasParser, $($name: HasParser,)* Sep: Separator> HasParser
+            for Tuple<($first, $($name),*), Sep>
+        {
+            #[into_parser(transparent)]
+            fn parser() -> _ {
+                (
+                    $first::parser(),
+                    $(Sep::parser().with($name::parser()),)*
+                )
+                    .skip(if Sep::TRAILING {
+                        Sep::parser().map(|_| ()).left()
+                    } else {
+                        value(()).right()
+                    })
+                    .map(Self::new)
+            }
+        }
+    }
+}
+
+tuple_parser!(A, B);
+tuple_parser!(A, B, C);
+tuple_parser!(A, B, C, D);
+tuple_parser!(A, B, C, D, E);
+
+/// What goes between the items of a `List`. The parser only has to match the separator; its
+/// output is thrown away. Separators that also implement `HasByteParser` can separate lists of
+/// bytes.
+pub trait Separator: HasParser {
+    /// Whether the separator also follows the last item, the way every line ends with a newline.
+    const TRAILING: bool = false;
+}
 
+/// Defines a unit struct that separates list items with the given string.
+///
+/// ```ignore
+/// separator!(CommaSpace, ", ");
+/// separator!(Row, "\n", trailing);
+/// ```
+#[macro_export]
+macro_rules! separator {
+    ($name:ident, $sep:expr) => {
+        $crate::separator!(@define $name, $sep, false);
+    };
+    ($name:ident, $sep:expr, trailing) => {
+        $crate::separator!(@define $name, $sep, true);
+    };
+    (@define $name:ident, $sep:expr, $trailing:expr) => {
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name;
+
+        impl $crate::HasParser for $name {
+            #[$crate::prelude::into_parser(transparent)]
+            fn parser() -> _ {
+                $crate::prelude::string($sep).map(|_| Self)
+            }
+        }
+
+        impl $crate::HasByteParser for $name {
+            #[$crate::prelude::into_byte_parser(transparent)]
+            fn byte_parser() -> _ {
+                $crate::byte_string($sep).map(|_| Self)
+            }
+        }
+
+        impl $crate::Separator for $name {
+            const TRAILING: bool = $trailing;
+        }
+    };
+}
+
+separator!(Comma, ",");
+separator!(Space, " ");
+
+/// The end of a line, which also follows the last item. A newline that starts a blank line is
+/// left for the `BlankLine` separating whatever the list is in.
 #[derive(Debug, Clone, Copy)]
 pub struct NewLine;
 
+impl HasParser for NewLine {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        let line_end = attempt((token('\n'), not_followed_by(token('\n'))));
+        let before_blank_line = look_ahead((token('\n'), token('\n')));
+        line_end.map(|_| Self).or(before_blank_line.map(|_| Self))
+    }
+}
+
+impl Separator for NewLine {
+    const TRAILING: bool = true;
+}
+
+/// An empty line between items: the newline ending the last line of an item and the empty line
+/// after it. `NewLine` leaves the newline before an empty line unmatched, so that lists of lines
+/// can be separated by blank lines too. It doesn't match the newlines at the end of the input.
+#[derive(Debug, Clone, Copy)]
+pub struct BlankLine;
+
+impl HasParser for BlankLine {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        (token('\n'), token('\n'), look_ahead(any())).map(|_| Self)
+    }
+}
+
+impl Separator for BlankLine {}
+
+/// A run of spaces and tabs. It doesn't cross lines, so it can be used for the items of a line.
+#[derive(Debug, Clone, Copy)]
+pub struct Whitespace;
+
+impl HasParser for Whitespace {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        skip_many1(one_of(" \t".chars())).map(|_| Self)
+    }
+}
+
+impl Separator for Whitespace {}
+
+/// Makes any separator also follow the last item.
+#[derive(Debug, Clone, Copy)]
+pub struct Trailing<Sep>(PhantomData<Sep>);
+
+impl<Sep: Separator> HasParser for Trailing<Sep> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        Sep::parser().map(|_| Self(PhantomData))
+    }
+}
+
+impl<Sep: Separator> Separator for Trailing<Sep> {
+    const TRAILING: bool = true;
+}
+
+/// How many items a `List` can have, the default being at least one.
+pub trait Length {
+    const MIN: usize;
+    const MAX: usize;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ZeroOrMore;
+
+impl Length for ZeroOrMore {
+    const MIN: usize = 0;
+    const MAX: usize = usize::MAX;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OneOrMore;
+
+impl Length for OneOrMore {
+    const MIN: usize = 1;
+    const MAX: usize = usize::MAX;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Exactly<const N: usize>;
+
+impl<const N: usize> Length for Exactly<N> {
+    const MIN: usize = N;
+    const MAX: usize = N;
+}
+
 #[derive(Debug, Clone, Copy)]
-pub struct Space;
+pub struct Between<const MIN: usize, const MAX: usize>;
+
+impl<const MIN: usize, const MAX: usize> Length for Between<MIN, MAX> {
+    const MIN: usize = MIN;
+    const MAX: usize = MAX;
+}
 
 #[derive(Clone, Debug)]
-pub struct List<T, Sep>(Vec<T>, PhantomData<Sep>);
+pub struct List<T, Sep, Len = OneOrMore>(Vec<T>, PhantomData<(Sep, Len)>);
 
-impl<T, Sep> From<Vec<T>> for List<T, Sep> {
+impl<T, Sep, Len> From<Vec<T>> for List<T, Sep, Len> {
     fn from(v: Vec<T>) -> Self {
         Self(v, PhantomData)
     }
 }
 
-impl<T: HasParser> HasParser for List<T, Comma> {
-    #[into_parser]
+impl<T: HasParser, Sep: Separator, Len: Length> HasParser for List<T, Sep, Len> {
+    #[into_parser(transparent)]
     fn parser() -> _ {
-        sep_by1(T::parser(), token(',')).map(|v: Vec<_>| v.into())
+        Self::parser_with_len(Len::MIN..=Len::MAX)
     }
-}
 
-impl<T: HasParser> HasParser for List<T, NewLine> {
-    #[into_parser]
-    fn parser() -> _ {
-        many1(T::parser().skip(token('\n'))).map(|v: Vec<_>| v.into())
+    fn name() -> String {
+        format!("List<{}>", T::name())
     }
 }
 
-impl<T: HasParser> HasParser for List<T, Space> {
-    #[into_parser]
-    fn parser() -> _ {
-        sep_by1(T::parser(), token(' ')).map(|v: Vec<_>| v.into())
+impl<T: HasParser, Sep: Separator, Len: Length> List<T, Sep, Len> {
+    /// Parses a list whose length is only known at runtime. A length of zero parses an empty list,
+    /// and an empty range of lengths is a parse error.
+    pub fn parser_with_len<Input>(len: impl RangeBounds<usize>) -> impl Parser<Input, Output = Self>
+    where
+        Input: combine::Stream<Token = char>,
+    {
+        list_parser(
+            list_len(len),
+            Sep::TRAILING,
+            <Self as HasParser>::name,
+            T::parser,
+            Sep::parser,
+        )
+        .map(|v: Vec<_>| v.into())
     }
 }
 
-impl<T, Sep> List<T, Sep> {
+/// The smallest and largest number of items a list of the given length can have, or `None` if the
+/// range is empty.
+fn list_len(len: impl RangeBounds<usize>) -> Option<(usize, usize)> {
+    let min = match len.start_bound() {
+        Bound::Included(&n) => Some(n),
+        Bound::Excluded(&n) => n.checked_add(1),
+        Bound::Unbounded => Some(0),
+    }?;
+    let max = match len.end_bound() {
+        Bound::Included(&n) => Some(n),
+        Bound::Excluded(&n) => n.checked_sub(1),
+        Bound::Unbounded => Some(usize::MAX),
+    }?;
+    (min <= max).then_some((min, max))
+}
+
+/// Parses the items of a `List`, from either characters or bytes.
+fn list_parser<Input, I, S>(
+    len: Option<(usize, usize)>,
+    trailing: bool,
+    name: fn() -> String,
+    item: impl Fn() -> I,
+    sep: impl Fn() -> S,
+) -> impl Parser<Input, Output = Vec<I::Output>>
+where
+    Input: combine::Stream,
+    I: Parser<Input>,
+    S: Parser<Input>,
+{
+    let (min, max) = match len {
+        Some(len) => len,
+        None => {
+            return value(())
+                .and_then(|()| {
+                    Err(StreamErrorFor::<Input>::message_static_message(
+                        "empty range of list lengths",
+                    ))
+                })
+                .left()
+        }
+    };
+
+    // Items are counted as they start, so that their errors can say which item failed.
+    let count = Rc::new(Cell::new(0));
+    let item = || {
+        let counter = count.clone();
+        with_context(
+            value(())
+                .map(move |()| counter.set(counter.get() + 1))
+                .with(item()),
+            ItemContext::new(count.clone(), name),
+        )
+    };
+
+    // Separators longer than a character must not commit to anything when they don't match.
+    let list = if max == 0 {
+        count_min_max(0, 0, item()).left()
+    } else if trailing {
+        count_min_max(min, max, item().skip(attempt(sep())))
+            .left()
+            .right()
+    } else {
+        let rest = count_min_max(min.saturating_sub(1), max - 1, attempt(sep()).with(item()));
+        let items = (item(), rest).map(|(first, mut rest): (_, Vec<_>)| {
+            rest.insert(0, first);
+            rest
+        });
+        if min == 0 {
+            optional(items).map(Option::unwrap_or_default).left()
+        } else {
+            items.right()
+        }
+        .right()
+        .right()
+    };
+
+    // The parser is reused for every list that an enclosing parser parses.
+    value(()).map(move |()| count.set(0)).with(list).right()
+}
+
+impl<T, Sep, Len> List<T, Sep, Len> {
     pub fn new() -> Self {
         Self(vec![], PhantomData)
     }
@@ -148,25 +751,31 @@ impl<T, Sep> List<T, Sep> {
     }
 }
 
-impl<'a, T, Sep> IntoIterator for &'a List<T, Sep> {
+impl<T, Sep, Len> Default for List<T, Sep, Len> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, Sep, Len> IntoIterator for &'a List<T, Sep, Len> {
     type Item = &'a T;
     type IntoIter = slice::Iter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        (&self.0).into_iter()
+        self.0.iter()
     }
 }
 
-impl<'a, T, Sep> IntoIterator for &'a mut List<T, Sep> {
+impl<'a, T, Sep, Len> IntoIterator for &'a mut List<T, Sep, Len> {
     type Item = &'a mut T;
     type IntoIter = slice::IterMut<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        (&mut self.0).into_iter()
+        self.0.iter_mut()
     }
 }
 
-impl<T, Sep> IntoIterator for List<T, Sep> {
+impl<T, Sep, Len> IntoIterator for List<T, Sep, Len> {
     type Item = T;
     type IntoIter = vec::IntoIter<T>;
 
@@ -175,25 +784,25 @@ impl<T, Sep> IntoIterator for List<T, Sep> {
     }
 }
 
-impl<T, Sep> iter::FromIterator<T> for List<T, Sep> {
+impl<T, Sep, Len> iter::FromIterator<T> for List<T, Sep, Len> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         Self(iter::FromIterator::from_iter(iter), PhantomData)
     }
 }
 
-impl<T, Sep> AsRef<[T]> for List<T, Sep> {
+impl<T, Sep, Len> AsRef<[T]> for List<T, Sep, Len> {
     fn as_ref(&self) -> &[T] {
         self.0.as_ref()
     }
 }
 
-impl<T, Sep> AsMut<[T]> for List<T, Sep> {
+impl<T, Sep, Len> AsMut<[T]> for List<T, Sep, Len> {
     fn as_mut(&mut self) -> &mut [T] {
         self.0.as_mut()
     }
 }
 
-impl<T, Sep> Deref for List<T, Sep> {
+impl<T, Sep, Len> Deref for List<T, Sep, Len> {
     type Target = [T];
 
     fn deref(&self) -> &[T] {
@@ -201,18 +810,368 @@ impl<T, Sep> Deref for List<T, Sep> {
     }
 }
 
-impl<T, Sep> DerefMut for List<T, Sep> {
+impl<T, Sep, Len> DerefMut for List<T, Sep, Len> {
     fn deref_mut(&mut self) -> &mut [T] {
         self.0.deref_mut()
     }
 }
 
-pub fn parse_str<T: HasParser>(
-    input: &str,
-) -> std::result::Result<T, easy::Errors<char, &str, position::SourcePosition>> {
+/// Sections of the input separated by blank lines. Either a tuple of sections of different types,
+/// or a `Vec` of any number of sections of the same type. Errors say which section they are in.
+/// Parts that take several arguments parse them as a tuple of sections.
+#[derive(Clone, Debug)]
+pub struct Sections<T>(pub T);
+
+macro_rules! tuple_sections {
+    ($first:ident $(, $name:ident $n:literal)*) => {
+        impl<$first: HasParser, $($name: HasParser),*> HasParser for Sections<($first, $($name),*)> {
+            #[into_parser(transparent)]
+            fn parser() -> _ {
+                (
+                    with_context($first::parser(), "in section 1"),
+                    $(with_context(
+                        BlankLine::parser().with($name::parser()),
+                        concat!("in section ", $n),
+                    ),)*
+                )
+                    .map(Self)
+            }
+        }
+    }
+}
+
+tuple_sections!(A, B 2);
+tuple_sections!(A, B 2, C 3);
+tuple_sections!(A, B 2, C 3, D 4);
+tuple_sections!(A, B 2, C 3, D 4, E 5);
+
+impl<T: HasParser> HasParser for Sections<Vec<T>> {
+    #[into_parser(transparent)]
+    fn parser() -> _ {
+        let index = Rc::new(Cell::new(0));
+        let counter = index.clone();
+        let section = value(()).then(move |()| {
+            counter.set(counter.get() + 1);
+            with_context(T::parser(), format!("in section {}", counter.get()))
+        });
+
+        // The parser is reused for every `Sections` that an enclosing parser parses.
+        value(())
+            .map(move |()| index.set(0))
+            .with(sep_by1(section, attempt(BlankLine::parser())))
+            .map(Self)
+    }
+}
+
+/// A parser for `T` that only builds `T::parser()` when it runs. A type's parser can't otherwise
+/// contain itself, so this is how types that nest, like brackets or expressions, parse their
+/// inner parts. It works the same way for types that refer to each other. `recursive_bytes` and
+/// `recursive_borrowed` do the same for `HasByteParser` and `HasBorrowedParser`.
+///
+/// ```ignore
+/// impl HasParser for Chunk {
+///     #[into_parser]
+///     fn parser() -> _ {
+///         many((char('('), recursive::<_, Chunk>(), char(')'))).map(Self)
+///     }
+/// }
+/// ```
+pub fn recursive<Input, T>() -> Recursive<Input, T>
+where
+    Input: combine::Stream<Token = char>,
+    T: HasParser,
+{
+    Recursive(PhantomData)
+}
+
+pub struct Recursive<Input, T>(PhantomData<fn(Input) -> T>);
+
+impl<Input, T> Parser<Input> for Recursive<Input, T>
+where
+    Input: combine::Stream<Token = char>,
+    T: HasParser,
+{
+    type Output = T;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<T, Input::Error> {
+        T::parser::<Input>().parse_lazy(input)
+    }
+
+    fn add_error(&mut self, errors: &mut combine::error::Tracked<Input::Error>) {
+        T::parser::<Input>().add_error(errors)
+    }
+}
+
+pub fn parse_str<T: HasParser>(input: &str) -> Result<T> {
     let (t, _): (T, _) = T::parser()
         .skip(spaces())
         .skip(eof())
-        .easy_parse(position::Stream::new(input))?;
+        .easy_parse(position::Stream::new(input))
+        .map_err(|e| SyntaxError::new(input, e))?;
     Ok(t)
 }
+
+/// Parses every line of `input` on its own, the way `List<T, NewLine>` parses them, but carries
+/// on past lines that fail so that all of them can be reported at once. Like the list, it
+/// requires every line to end with a newline and skips whitespace after the last one.
+pub fn parse_lines<T: HasParser>(input: &str) -> Result<List<T, NewLine>> {
+    let mut end = input.trim_end().len();
+    if input[end..].starts_with('\n') {
+        end += 1;
+    }
+    if end == 0 {
+        // The list's own error for having no items.
+        return parse_str(input);
+    }
+
+    let mut items = List::new();
+    let mut errors = vec![];
+    for (number, line) in (1..).zip(input[..end].split_inclusive('\n')) {
+        let result = T::parser()
+            .skip(NewLine::parser())
+            .skip(eof())
+            .easy_parse(position::Stream::new(line));
+        match result {
+            Ok((item, _)) => items.push(item),
+            Err(e) => {
+                let mut e = SyntaxError::new(line, e);
+                e.line = number;
+                e.context.insert(
+                    0,
+                    format!("in item {} of {}", number, List::<T, NewLine>::name()),
+                );
+                errors.push(e);
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(items)
+    } else {
+        Err(Error::Many(errors))
+    }
+}
+
+#[test]
+fn separators() {
+    separator!(Arrow, " -> ");
+
+    let arrows = parse_str::<List<u32, Arrow>>("1 -> 2 -> 3").unwrap();
+    assert_eq!(&arrows[..], [1, 2, 3]);
+    let words = parse_str::<List<List<u32, Whitespace>, NewLine>>("1  2\t3\n4 5\n").unwrap();
+    assert_eq!(&words[0][..], [1, 2, 3]);
+    let sections = parse_str::<List<List<u32, NewLine>, BlankLine>>("1\n2\n\n3\n").unwrap();
+    assert_eq!(sections.len(), 2);
+    let terminated = parse_str::<List<u32, Trailing<Comma>>>("1,2,").unwrap();
+    assert_eq!(&terminated[..], [1, 2]);
+}
+
+#[test]
+fn lengths() {
+    assert!(parse_str::<List<u32, Comma, ZeroOrMore>>("")
+        .unwrap()
+        .is_empty());
+    assert!(parse_str::<List<u32, NewLine, ZeroOrMore>>("")
+        .unwrap()
+        .is_empty());
+    assert!(parse_str::<List<u32, Comma>>("").is_err());
+    assert_eq!(
+        parse_str::<List<u32, Space, Exactly<3>>>("1 2 3")
+            .unwrap()
+            .len(),
+        3
+    );
+    assert!(parse_str::<List<u32, Space, Exactly<3>>>("1 2").is_err());
+    assert!(parse_str::<List<u32, Space, Exactly<3>>>("1 2 3 4").is_err());
+    assert!(parse_str::<List<u32, NewLine, Between<1, 2>>>("1\n2\n3\n").is_err());
+
+    let (list, _) = List::<u32, Comma>::parser_with_len(2..=2)
+        .parse("1,2,3")
+        .unwrap();
+    assert_eq!(&list[..], [1, 2]);
+
+    let (list, rest) = List::<u32, Comma>::parser_with_len(0..=0)
+        .parse("1,2")
+        .unwrap();
+    assert!(list.is_empty());
+    assert_eq!(rest, "1,2");
+    assert!(parse_str::<List<u32, NewLine, Exactly<0>>>("")
+        .unwrap()
+        .is_empty());
+    assert!(List::<u32, Comma>::parser_with_len(..0).parse("1").is_err());
+}
+
+#[test]
+fn sections() {
+    let Sections((numbers, Sections(groups))) = parse_str::<
+        Sections<(List<u32, Comma>, Sections<Vec<List<u32, NewLine>>>)>,
+    >("1,2\n\n3\n4\n\n5\n")
+    .unwrap();
+    assert_eq!(&numbers[..], [1, 2]);
+    assert_eq!(groups.len(), 2);
+
+    let error = parse_str::<Sections<(u32, u32, u32)>>("1\n\n2\n\nx\n").unwrap_err();
+    assert!(error.to_string().contains("in section 3"));
+    // Lines count from the start of the input, not of the section.
+    assert!(matches!(&error, Error::Syntax(e) if e.line == 5));
+    let error = parse_str::<Sections<Vec<List<u32, NewLine>>>>("1\n\n2\n3x\n").unwrap_err();
+    assert!(error.to_string().contains("in section 2"));
+    let error = parse_str::<List<Sections<Vec<u32>>, Space>>("1\n\n2 3\n\nx\n").unwrap_err();
+    assert!(error.to_string().contains("in section 2"));
+
+    let blocks: List<List<u32, NewLine>, BlankLine> = parse_str("1\n2\n\n3\n").unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert!(parse_str::<List<u32, BlankLine>>("1\n\n2").is_ok());
+    assert!(parse_str::<List<u32, BlankLine>>("1\n2").is_err());
+}
+
+#[test]
+fn std_types() {
+    assert_eq!(parse_str::<[char; 2]>("AB").unwrap(), ['A', 'B']);
+    assert!(parse_str::<[char; 2]>("ABC").is_err());
+    assert_eq!(parse_str::<Vec<bool>>("truefalse").unwrap(), [true, false]);
+    assert_eq!(parse_str::<Option<u32>>("").unwrap(), None);
+    assert_eq!(*parse_str::<Box<u32>>("4").unwrap(), 4);
+    assert_eq!(parse_str::<BTreeSet<char>>("abca").unwrap().len(), 3);
+    assert_eq!(parse_str::<HashSet<char>>("abca").unwrap().len(), 3);
+    let lines = parse_str::<List<Vec<char>, NewLine>>("ab\ncd\n").unwrap();
+    assert_eq!(lines[1], ['c', 'd']);
+
+    let pair = parse_str::<Tuple<(i32, u8), Comma>>("-1,2").unwrap();
+    assert_eq!(pair.into_inner(), (-1, 2));
+    let triple = parse_str::<Tuple<(u8, char, u8), Space>>("1 x 2").unwrap();
+    assert_eq!(*triple, (1, 'x', 2));
+}
+
+#[test]
+fn syntax_errors() {
+    let error = parse_str::<List<Tuple<(u32, u32), Comma>, NewLine>>("1,2\n3,x4\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2, column 3, in item 2 of List<Tuple<(u32, u32), Comma>>: unexpected `x`, \
+         expected digit\n  |\n2 | 3,x4\n  |   ^"
+    );
+}
+
+#[test]
+fn all_line_errors() {
+    let lines = parse_lines::<Tuple<(u32, u32), Comma>>("1,2\n3,4\n").unwrap();
+    assert_eq!(*lines[1], (3, 4));
+
+    let errors = match parse_lines::<Tuple<(u32, u32), Comma>>("1,2\nx,4\n5,6\n7,\n") {
+        Err(Error::Many(errors)) => errors,
+        _ => panic!(),
+    };
+    let lines: Vec<_> = errors.iter().map(|e| (e.line, e.column)).collect();
+    assert_eq!(lines, [(2, 1), (4, 3)]);
+    assert_eq!(errors[1].source_line, "7,");
+    assert_eq!(
+        errors[1].context[0],
+        "in item 4 of List<Tuple<(u32, u32), Comma>>"
+    );
+
+    assert!(parse_lines::<u32>("1\n2\n\n").is_ok());
+    assert!(parse_lines::<u32>("1\n2").is_err());
+    assert!(parse_lines::<u32>("").is_err());
+}
+
+#[test]
+fn error_context() {
+    struct Vent;
+
+    impl HasParser for Vent {
+        #[into_parser]
+        fn parser() -> _ {
+            Tuple::<(u32, u32), Comma>::parser().map(|_| Self)
+        }
+
+        fn name() -> String {
+            "vent".into()
+        }
+    }
+
+    let error = match parse_str::<List<List<Vent, Space>, NewLine>>("1,2 3,4\n5,6 7,\n") {
+        Err(Error::Syntax(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(
+        error.context,
+        [
+            "in item 2 of List<List<vent>>",
+            "in item 2 of List<vent>",
+            "in vent"
+        ]
+    );
+    assert_eq!(short_type_name::<List<u8, NewLine>>(), "List<u8, NewLine>");
+
+    let error = match parse_str::<List<u32, Comma>>("1,x") {
+        Err(Error::Syntax(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(error.context, ["in item 2 of List<u32>"]);
+    let mut message = u32::parser().message("in range 0-9").skip(eof());
+    let error = message.easy_parse(position::Stream::new("x")).unwrap_err();
+    let error = SyntaxError::new("x", error);
+    assert_eq!(error.messages, ["in range 0-9"]);
+    assert!(error.context.is_empty());
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(parse_str::<i32>("-42").unwrap(), -42);
+    assert_eq!(parse_str::<i8>("+7").unwrap(), 7);
+    assert_eq!(parse_str::<f64>("-1.5e3").unwrap(), -1500.0);
+    assert_eq!(parse_str::<f32>("2.").unwrap(), 2.0);
+    assert_eq!(parse_str::<Hex<u16>>("BEEF").unwrap(), Hex(0xbeef));
+    assert_eq!(parse_str::<Bin<u8>>("1011").unwrap(), Bin(0b1011));
+
+    let overflow = parse_str::<List<u8, Comma>>("1,300").unwrap_err();
+    assert!(matches!(&overflow, Error::Syntax(e) if e.column == 3));
+    assert!(overflow.to_string().contains("number too large"));
+    assert_eq!(parse_str::<i8>("-128").unwrap(), i8::MIN);
+    assert!(parse_str::<i8>("-129").is_err());
+    assert!(parse_str::<i8>("128").is_err());
+    assert_eq!(parse_str::<u64>(&u64::MAX.to_string()).unwrap(), u64::MAX);
+}
+
+#[test]
+fn recursive_types() {
+    #[derive(Debug, PartialEq)]
+    enum Expr {
+        Number(u32),
+        List(Vec<Expr>),
+    }
+
+    impl HasParser for Expr {
+        #[into_parser]
+        fn parser() -> _ {
+            let list = between(
+                token('['),
+                token(']'),
+                sep_by(recursive::<_, Expr>(), token(',')),
+            );
+            u32::parser().map(Self::Number).or(list.map(Self::List))
+        }
+    }
+
+    use Expr::*;
+    assert_eq!(
+        parse_str::<Expr>("[1,[],[2,[3]]]").unwrap(),
+        List(vec![
+            Number(1),
+            List(vec![]),
+            List(vec![Number(2), List(vec![Number(3)])])
+        ])
+    );
+
+    let e = match parse_str::<Expr>("[1,[x]]") {
+        Err(Error::Syntax(e)) => e,
+        r => panic!("{:?}", r),
+    };
+    assert_eq!(e.column, 5);
+    assert_eq!(
+        e.expected,
+        [Token::Description("digit".into()), Token::Char('[')]
+    );
+    assert_eq!(e.context, ["in Expr", "in Expr", "in Expr"]);
+}
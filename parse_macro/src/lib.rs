@@ -3,6 +3,7 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 use quote::quote;
 use std::matches;
+use syn::parse::{ParseStream, Parser as _};
 use syn::*;
 
 fn verify_signature(sig: &Signature) -> Result<()> {
@@ -33,25 +34,127 @@ fn verify_signature(sig: &Signature) -> Result<()> {
     }
 }
 
-fn into_parser_inner(input: TokenStream) -> Result<TokenStream> {
+/// Unless the attribute says `transparent`, errors from the parser say which type was being
+/// parsed.
+fn is_transparent(input: ParseStream) -> Result<bool> {
+    if input.is_empty() {
+        return Ok(false);
+    }
+    let ident: Ident = input.parse()?;
+    if ident != "transparent" {
+        return Err(Error::new(ident.span(), "expected `transparent`"));
+    }
+    Ok(true)
+}
+
+/// `#[into_borrowed_parser]` first names the lifetime of the input, as in `'a` or
+/// `'a, transparent`.
+fn borrowed_attr(input: ParseStream) -> Result<(Lifetime, bool)> {
+    let lifetime: Lifetime = input.parse()?;
+    if input.is_empty() {
+        return Ok((lifetime, false));
+    }
+    input.parse::<Token![,]>()?;
+    Ok((lifetime, is_transparent(input)?))
+}
+
+/// The trait each of the `into_*parser` attributes implements, and the streams it parses.
+struct Flavor {
+    parser_type: &'static str,
+    stream: String,
+    has_parser: String,
+}
+
+fn into_parser_inner(flavor: Flavor, transparent: bool, input: TokenStream) -> Result<TokenStream> {
     let input: ItemFn = parse(input)?;
     verify_signature(&input.sig)?;
 
     let name = input.sig.ident;
     let block = input.block;
+    let has_parser: Path = parse_str(&flavor.has_parser)?;
+    let body = if transparent {
+        quote!(#block)
+    } else {
+        quote!({ with_context(#block, TypeContext::new(<Self as #has_parser>::name)) })
+    };
+    let parser_type = Ident::new(flavor.parser_type, name.span());
+    let stream: PathSegment = parse_str(&flavor.stream)?;
+    // Stable Rust can't name the type of the parser, so there it is built inside functions whose
+    // pointers make up the parser instead.
+    if cfg!(feature = "stable") {
+        return Ok(quote! {
+            type #parser_type<Input: combine::#stream> = StableParser<Input, Self>;
+
+            fn #name<Input>() -> Self::#parser_type<Input>
+            where
+                Input: ::combine::#stream,
+            {
+                StableParser::new(
+                    |input| run_stable_parser::<Input, Self, _>(input, #body),
+                    |errors| add_stable_parser_error::<Input, Self, _>(errors, #body),
+                )
+            }
+        }
+        .into());
+    }
     Ok(quote! {
-        type Parser<Input: combine::Stream<Token = char>> = impl Parser<Input, Output = Self>;
+        type #parser_type<Input: combine::#stream> = impl Parser<Input, Output = Self>;
 
-        fn #name<Input>() -> Self::Parser<Input>
+        fn #name<Input>() -> Self::#parser_type<Input>
         where
-            Input: ::combine::Stream<Token = char>,
-        #block
-    }.into())
+            Input: ::combine::#stream,
+        #body
+    }
+    .into())
+}
+
+#[proc_macro_attribute]
+pub fn into_parser(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let flavor = Flavor {
+        parser_type: "Parser",
+        stream: "Stream<Token = char>".into(),
+        has_parser: "HasParser".into(),
+    };
+    match is_transparent
+        .parse(attr)
+        .and_then(|transparent| into_parser_inner(flavor, transparent, input))
+    {
+        Ok(v) => v,
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+/// Like `#[into_parser]`, for `HasByteParser::byte_parser`.
+#[proc_macro_attribute]
+pub fn into_byte_parser(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let flavor = Flavor {
+        parser_type: "ByteParser",
+        stream: "Stream<Token = u8>".into(),
+        has_parser: "HasByteParser".into(),
+    };
+    match is_transparent
+        .parse(attr)
+        .and_then(|transparent| into_parser_inner(flavor, transparent, input))
+    {
+        Ok(v) => v,
+        Err(e) => e.into_compile_error().into(),
+    }
 }
 
+/// Like `#[into_parser]`, for `HasBorrowedParser::borrowed_parser`. The attribute names the
+/// lifetime of the input, as in `#[into_borrowed_parser('a)]` inside
+/// `impl<'a> HasBorrowedParser<'a> for Cave<'a>`.
 #[proc_macro_attribute]
-pub fn into_parser(_attr: TokenStream, input: TokenStream) -> TokenStream {
-    match into_parser_inner(input) {
+pub fn into_borrowed_parser(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let inner = |(lifetime, transparent): (Lifetime, bool)| {
+        let flavor = Flavor {
+            parser_type: "BorrowedParser",
+            stream: format!("RangeStream<Token = char, Range = &{} str>", lifetime),
+            has_parser: format!("HasBorrowedParser<{}>", lifetime),
+        };
+        into_parser_inner(flavor, transparent, input)
+    };
+    match borrowed_attr.parse(attr).and_then(inner) {
         Ok(v) => v,
         Err(e) => e.into_compile_error().into(),
     }
@@ -1,5 +1,5 @@
-#![feature(generic_associated_types)]
-#![feature(type_alias_impl_trait)]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
 
 use advent::prelude::*;
 
@@ -32,12 +32,12 @@ fn run_simluation(starting_fish: List<usize, Comma>, days: usize) -> usize {
 
 #[part_one]
 fn part_one(fish: List<usize, Comma>) -> usize {
-    run_simluation(fish, 80)
+    run_simluation(fish, param("part_one.days", 80))
 }
 
 #[part_two]
 fn part_two(fish: List<usize, Comma>) -> usize {
-    run_simluation(fish, 256)
+    run_simluation(fish, param("part_two.days", 256))
 }
 
 harness!();
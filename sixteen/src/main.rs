@@ -1,5 +1,5 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 use byteorder::ReadBytesExt as _;
@@ -8,23 +8,24 @@ use std::io;
 #[derive(Debug)]
 struct Hex(Vec<u8>);
 
+/// The byte spelled by two hex digits, which both the `char` and byte parsers match.
+fn hex_byte(high: char, low: char) -> u8 {
+    (high.to_digit(16).unwrap() << 4 | low.to_digit(16).unwrap()) as u8
+}
+
 impl HasParser for Hex {
     #[into_parser]
     fn parser() -> _ {
-        let hex_digit = || {
-            choice((
-                digit(),
-                token('A'),
-                token('B'),
-                token('C'),
-                token('D'),
-                token('E'),
-                token('F'),
-            ))
-        };
-        let byte = (hex_digit(), hex_digit())
-            .map(|(c1, c2)| u8::from_str_radix(&format!("{}{}", c1, c2), 16).unwrap());
-        many1(byte).map(Self)
+        many1((hex_digit(), hex_digit()).map(|(high, low)| hex_byte(high, low))).map(Self)
+    }
+}
+
+impl HasByteParser for Hex {
+    #[into_byte_parser]
+    fn byte_parser() -> _ {
+        let hex_digit = combine::parser::byte::hex_digit;
+        many1((hex_digit(), hex_digit()).map(|(high, low)| hex_byte(high as char, low as char)))
+            .map(Self)
     }
 }
 
@@ -270,4 +271,14 @@ fn part_two(bytes: Hex) -> u64 {
     p.evaluate()
 }
 
+#[test]
+fn packet_trees() {
+    for example in ["example3", "example7", "example14"] {
+        let path = format!("{}/{}.txt", env!("CARGO_MANIFEST_DIR"), example);
+        let bytes: Hex = parse_bytes(&std::fs::read(path).unwrap()).unwrap();
+        let mut r = BitReader::new(&bytes.0[..]);
+        advent::assert_debug_snapshot!(example, Packet::from_bits(&mut r).unwrap());
+    }
+}
+
 harness!();
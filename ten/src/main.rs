@@ -1,49 +1,41 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
-use combine::stream::easy::{Error, Errors, Info};
-use combine::stream::position;
 use std::matches;
 
-parser! {
-    fn valid_chunk_parser_recurse[Input]()(Input) -> ValidChunk
-        where [Input: Stream<Token = char>]
-    {
-        ValidChunk::parser()
-    }
-}
-
 #[derive(Debug)]
 enum ChunkValidationError {
     Incomplete(Option<char>),
     Corrupt(usize),
+    Other(advent::parse::Error),
 }
 
 fn closing_char(c: char) -> bool {
     matches!(c, ')' | '}' | ']' | '>')
 }
 
-impl<'a> From<Errors<char, &'a str, position::SourcePosition>> for ChunkValidationError {
-    fn from(e: Errors<char, &'a str, position::SourcePosition>) -> Self {
-        if e.errors
+impl From<advent::parse::Error> for ChunkValidationError {
+    fn from(e: advent::parse::Error) -> Self {
+        let e = match e {
+            advent::parse::Error::Syntax(e) => e,
+            e => return Self::Other(e),
+        };
+        if e.unexpected
             .iter()
-            .any(|e| matches!(e, Error::Unexpected(Info::Static("end of input"))))
+            .any(|t| matches!(t, Token::Description(d) if d == "end of input"))
         {
             let c = e
-                .errors
+                .expected
                 .iter()
-                .filter_map(|e| {
-                    if let Error::Expected(Info::Token(c)) = e {
-                        closing_char(*c).then(|| *c)
-                    } else {
-                        None
-                    }
+                .filter_map(|t| match *t {
+                    Token::Char(c) => closing_char(c).then_some(c),
+                    _ => None,
                 })
                 .next();
             Self::Incomplete(c)
         } else {
-            Self::Corrupt(e.position.column as usize)
+            Self::Corrupt(e.column)
         }
     }
 }
@@ -55,10 +47,10 @@ impl HasParser for ValidChunk {
     #[into_parser]
     fn parser() -> _ {
         many(choice((
-            (char('('), valid_chunk_parser_recurse(), char(')')),
-            (char('<'), valid_chunk_parser_recurse(), char('>')),
-            (char('['), valid_chunk_parser_recurse(), char(']')),
-            (char('{'), valid_chunk_parser_recurse(), char('}')),
+            (char('('), recursive::<_, ValidChunk>(), char(')')),
+            (char('<'), recursive::<_, ValidChunk>(), char('>')),
+            (char('['), recursive::<_, ValidChunk>(), char(']')),
+            (char('{'), recursive::<_, ValidChunk>(), char('}')),
         )))
         .map(|_: Vec<_>| Self)
     }
@@ -128,8 +120,12 @@ fn score_for_autocomplete_char(c: char) -> u64 {
 fn part_one(lines: List<Chunk, NewLine>) -> u32 {
     let mut score = 0;
     for l in lines {
-        if let Err(ChunkValidationError::Corrupt(p)) = l.validate() {
-            score += score_for_bad_char(l.0.chars().nth(p - 1).unwrap());
+        match l.validate() {
+            Err(ChunkValidationError::Corrupt(p)) => {
+                score += score_for_bad_char(l.0.chars().nth(p - 1).unwrap());
+            }
+            Err(ChunkValidationError::Other(e)) => panic!("{}", e),
+            _ => {}
         }
     }
     score
@@ -139,14 +135,18 @@ fn part_one(lines: List<Chunk, NewLine>) -> u32 {
 fn part_two(lines: List<Chunk, NewLine>) -> u64 {
     let mut scores = vec![];
     for l in lines {
-        if let Err(ChunkValidationError::Incomplete(_)) = l.validate() {
-            let mut score = 0;
-            let completion = l.autocomplete();
-            for c in completion.chars() {
-                score *= 5;
-                score += score_for_autocomplete_char(c);
+        match l.validate() {
+            Err(ChunkValidationError::Incomplete(_)) => {
+                let mut score = 0;
+                let completion = l.autocomplete();
+                for c in completion.chars() {
+                    score *= 5;
+                    score += score_for_autocomplete_char(c);
+                }
+                scores.push(score);
             }
-            scores.push(score);
+            Err(ChunkValidationError::Other(e)) => panic!("{}", e),
+            _ => {}
         }
     }
     scores.sort();
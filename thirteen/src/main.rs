@@ -1,21 +1,10 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
 use std::fmt;
 
-#[derive(Debug)]
-struct Coordinate {
-    x: usize,
-    y: usize,
-}
-
-impl HasParser for Coordinate {
-    #[into_parser]
-    fn parser() -> _ {
-        (usize::parser().skip(token(',')), usize::parser()).map(|(x, y)| Self { x, y })
-    }
-}
+type Coordinate = Tuple<(usize, usize), Comma>;
 
 #[derive(Debug)]
 enum Fold {
@@ -33,48 +22,23 @@ impl HasParser for Fold {
     }
 }
 
-#[derive(Debug)]
-struct Input {
-    coordinates: List<Coordinate, NewLine>,
-    folds: List<Fold, NewLine>,
-}
-
-impl HasParser for Input {
-    #[into_parser]
-    fn parser() -> _ {
-        let coordinates = List::<Coordinate, NewLine>::parser();
-        let folds = List::<Fold, NewLine>::parser();
-        (coordinates.skip(token('\n')), folds)
-            .map(|(coordinates, folds)| Self { coordinates, folds })
-    }
-}
-
 struct Paper {
     grid: Vec<Vec<bool>>,
     folds: Vec<Fold>,
 }
 
 impl Paper {
-    fn from_input(input: Input) -> Self {
-        let max_x = input
-            .coordinates
-            .iter()
-            .map(|c| c.x as usize)
-            .max()
-            .unwrap();
-        let max_y = input
-            .coordinates
-            .iter()
-            .map(|c| c.y as usize)
-            .max()
-            .unwrap();
+    fn new(coordinates: List<Coordinate, NewLine>, folds: List<Fold, NewLine, ZeroOrMore>) -> Self {
+        let max_x = coordinates.iter().map(|c| c.0).max().unwrap();
+        let max_y = coordinates.iter().map(|c| c.1).max().unwrap();
         let mut grid = vec![vec![false; max_x + 1]; max_y + 1];
-        for c in input.coordinates {
-            grid[c.y as usize][c.x as usize] = true;
+        for c in coordinates {
+            let (x, y) = c.into_inner();
+            grid[y][x] = true;
         }
         Self {
             grid,
-            folds: input.folds.into_iter().rev().collect(),
+            folds: folds.into_iter().rev().collect(),
         }
     }
 
@@ -135,17 +99,33 @@ impl fmt::Debug for Paper {
 }
 
 #[part_one]
-fn part_one(i: Input) -> usize {
-    let mut paper = Paper::from_input(i);
+fn part_one(
+    coordinates: List<Coordinate, NewLine>,
+    folds: List<Fold, NewLine, ZeroOrMore>,
+) -> usize {
+    let mut paper = Paper::new(coordinates, folds);
     paper.fold();
     paper.num_dots()
 }
 
 #[part_two]
-fn part_two(i: Input) -> String {
-    let mut paper = Paper::from_input(i);
+fn part_two(
+    coordinates: List<Coordinate, NewLine>,
+    folds: List<Fold, NewLine, ZeroOrMore>,
+) -> Grid {
+    let mut paper = Paper::new(coordinates, folds);
+    while paper.fold() {}
+    paper.grid.into()
+}
+
+#[test]
+fn example_folds() {
+    let Sections((coordinates, folds)) = parse_str(include_str!("../example.txt")).unwrap();
+    let mut paper = Paper::new(coordinates, folds);
+    paper.fold();
+    advent::assert_debug_snapshot!("example_first_fold", paper);
     while paper.fold() {}
-    format!("\n{:?}", paper)
+    advent::assert_snapshot!("example_folded", Grid::from(paper.grid));
 }
 
 harness!();
@@ -1,5 +1,5 @@
-#![feature(generic_associated_types)]
-#![feature(type_alias_impl_trait)]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
 
 use advent::prelude::*;
 
@@ -1,7 +1,8 @@
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
 
 use advent::prelude::*;
+use combine::parser::range::take_while1;
 use multiset::HashMultiSet;
 use std::collections::{HashMap, HashSet};
 use std::{fmt, matches};
@@ -13,19 +14,19 @@ enum CaveKind {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-struct Cave {
+struct Cave<'a> {
     kind: CaveKind,
-    name: String,
+    name: &'a str,
 }
 
-impl HasParser for Cave {
-    #[into_parser]
-    fn parser() -> _ {
-        let big = many1(upper()).map(|name| Self {
+impl<'a> HasBorrowedParser<'a> for Cave<'a> {
+    #[into_borrowed_parser('a)]
+    fn borrowed_parser() -> _ {
+        let big = take_while1(char::is_uppercase).map(|name| Self {
             kind: CaveKind::Big,
             name,
         });
-        let small = many1(lower()).map(|name| Self {
+        let small = take_while1(char::is_lowercase).map(|name| Self {
             kind: CaveKind::Small,
             name,
         });
@@ -34,13 +35,13 @@ impl HasParser for Cave {
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
-enum Node {
-    Cave(Cave),
+enum Node<'a> {
+    Cave(Cave<'a>),
     Start,
     End,
 }
 
-impl fmt::Debug for Node {
+impl fmt::Debug for Node<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Start => write!(f, "start"),
@@ -50,7 +51,7 @@ impl fmt::Debug for Node {
     }
 }
 
-impl Node {
+impl Node<'_> {
     fn is_small_cave(&self) -> bool {
         matches!(
             self,
@@ -70,35 +71,35 @@ impl Node {
     }
 }
 
-impl HasParser for Node {
-    #[into_parser]
-    fn parser() -> _ {
+impl<'a> HasBorrowedParser<'a> for Node<'a> {
+    #[into_borrowed_parser('a)]
+    fn borrowed_parser() -> _ {
         let start = string("start").map(|_| Self::Start);
         let end = string("end").map(|_| Self::End);
-        let cave = Cave::parser().map(Self::Cave);
+        let cave = Cave::borrowed_parser().map(Self::Cave);
         choice((attempt(start), attempt(end), cave))
     }
 }
 
 #[derive(Debug)]
-struct CaveSystem {
-    edges: HashMap<Node, HashSet<Node>>,
+struct CaveSystem<'a> {
+    edges: HashMap<Node<'a>, HashSet<Node<'a>>>,
 }
 
 #[derive(Clone)]
-struct Cursor {
-    node: Node,
+struct Cursor<'a> {
+    node: Node<'a>,
     index: usize,
 }
 
-impl fmt::Debug for Cursor {
+impl fmt::Debug for Cursor<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", &self.node)
     }
 }
 
-impl Cursor {
-    fn new(node: Node) -> Self {
+impl<'a> Cursor<'a> {
+    fn new(node: Node<'a>) -> Self {
         Self { node, index: 0 }
     }
 
@@ -111,12 +112,12 @@ impl Cursor {
 }
 
 #[derive(Debug)]
-struct PathStack {
-    stack: Vec<Cursor>,
-    set: HashMultiSet<Node>,
+struct PathStack<'a> {
+    stack: Vec<Cursor<'a>>,
+    set: HashMultiSet<Node<'a>>,
 }
 
-impl PathStack {
+impl<'a> PathStack<'a> {
     fn new() -> Self {
         Self {
             stack: vec![Cursor::start()],
@@ -124,7 +125,7 @@ impl PathStack {
         }
     }
 
-    fn push(&mut self, c: Cursor) {
+    fn push(&mut self, c: Cursor<'a>) {
         self.set.insert_times(c.node.clone(), 1);
         self.stack.push(c);
     }
@@ -149,18 +150,18 @@ impl PathStack {
         self.set.contains(n)
     }
 
-    fn last(&self) -> Option<Cursor> {
+    fn last(&self) -> Option<Cursor<'a>> {
         self.stack.last().map(|c| c.clone())
     }
 
-    fn last_mut(&mut self) -> &mut Cursor {
+    fn last_mut(&mut self) -> &mut Cursor<'a> {
         self.stack.last_mut().unwrap()
     }
 }
 
-impl CaveSystem {
-    fn from_edges(edges_in: HashSet<(Node, Node)>) -> Self {
-        let mut edges = HashMap::<Node, HashSet<Node>>::new();
+impl<'a> CaveSystem<'a> {
+    fn from_edges(edges_in: HashSet<(Node<'a>, Node<'a>)>) -> Self {
+        let mut edges = HashMap::<Node<'a>, HashSet<Node<'a>>>::new();
         for (s, d) in edges_in {
             edges.entry(s.clone()).or_default().insert(d.clone());
             edges.entry(d).or_default().insert(s);
@@ -168,13 +169,15 @@ impl CaveSystem {
         Self { edges }
     }
 
-    fn num_paths(&self, small_cave_twice: bool) -> u64 {
+    fn num_paths(&self, small_cave_twice: bool, progress: &Progress) -> u64 {
         let mut path = PathStack::new();
+        progress.set_label("paths");
 
         let mut num_paths = 0;
         'outer: while let Some(c) = path.last() {
             if c.node.is_end() {
                 num_paths += 1;
+                progress.inc();
                 path.pop();
                 continue;
             }
@@ -203,22 +206,25 @@ impl CaveSystem {
     }
 }
 
-impl HasParser for CaveSystem {
-    #[into_parser]
-    fn parser() -> _ {
-        let line = (Node::parser().skip(token('-')), Node::parser());
+impl<'a> HasBorrowedParser<'a> for CaveSystem<'a> {
+    #[into_borrowed_parser('a)]
+    fn borrowed_parser() -> _ {
+        let line = (
+            Node::borrowed_parser().skip(token('-')),
+            Node::borrowed_parser(),
+        );
         many1(line.skip(token('\n'))).map(Self::from_edges)
     }
 }
 
-#[part_one]
-fn part_one(system: CaveSystem) -> u64 {
-    system.num_paths(false)
+#[part_one(borrowed)]
+fn part_one(system: CaveSystem<'_>, progress: &Progress) -> u64 {
+    system.num_paths(false, progress)
 }
 
-#[part_two]
-fn part_two(system: CaveSystem) -> u64 {
-    system.num_paths(true)
+#[part_two(borrowed)]
+fn part_two(system: CaveSystem<'_>, progress: &Progress) -> u64 {
+    system.num_paths(true, progress)
 }
 
 harness!();
@@ -1,5 +1,5 @@
-#![feature(generic_associated_types)]
-#![feature(type_alias_impl_trait)]
+#![cfg_attr(not(feature = "stable"), feature(generic_associated_types))]
+#![cfg_attr(not(feature = "stable"), feature(type_alias_impl_trait))]
 
 use advent::prelude::*;
 
@@ -14,8 +14,7 @@ impl HasParser for Direction {
     #[into_parser]
     fn parser() -> _ {
         let dir = string("forward").or(string("up")).or(string("down"));
-        let num = many1(digit()).map(|n: String| n.parse::<u32>().unwrap());
-        (dir, spaces(), num).map(|(d, _, n)| match d {
+        (dir, spaces(), u32::parser()).map(|(d, _, n)| match d {
             "forward" => Self::Forward(n),
             "up" => Self::Up(n),
             "down" => Self::Down(n),